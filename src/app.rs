@@ -1,62 +1,520 @@
+use std::path::{Path, PathBuf};
+
 use eframe::{App, CreationContext};
-use egui_snarl::{ui::SnarlStyle, Snarl};
+use egui::{Key, KeyboardShortcut, Modifiers};
+use egui_snarl::{NodeId, Snarl};
+
+use crate::{
+    document::{self, Document},
+    interface::Interface,
+    nodes::Nodes,
+    render,
+    solver::Solver,
+    viewer::{self, NodeGraphViewer, ViewerState},
+};
+
+const MAX_RECENT_FILES: usize = 8;
+
+const NEW_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::N);
+const OPEN_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::O);
+const SAVE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::S);
+const SAVE_AS_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::S);
+
+/// Actions which replace the current document, and
+/// have to be confirmed if it has unsaved changes
+enum Replace {
+    New,
+    /// Opens the given file, or asks for one if there is none
+    Open(Option<PathBuf>),
+}
 
-use crate::{nodes, viewer::NodeGraphViewer};
-// TODO: I also need a generic solver which can solve a [`Snarl`]
-// without showing any ui. This will allow hosting graphs in an axum
-// server and sending json serialized inputs and getting the result back
 pub struct NodeGraphApp {
-    snarl: Snarl<nodes::Nodes>,
-    style: SnarlStyle,
+    document: Document,
+    /// The file the document was loaded from or last saved to
+    path: Option<PathBuf>,
+    /// [`Document::fingerprint`] at the time the document was last saved
+    saved: u64,
+    /// The document changed since it was last saved or opened
+    modified: bool,
+    /// Where every node was placed after the last frame,
+    /// to notice nodes being added, removed or moved
+    layout: Vec<(NodeId, egui::Pos2)>,
+    recent_files: Vec<PathBuf>,
+    /// Waiting for the user to decide what happens to unsaved changes
+    pending: Option<Replace>,
+    /// The last error of reading or writing a file
+    file_error: Option<String>,
+    /// A stored document which could not be read, kept
+    /// around so it is not overwritten by the next save
+    unrestorable: Option<String>,
+    /// The window title sent to the viewport last
+    title: String,
+    solver: Solver,
+    viewer: ViewerState,
 }
 
 impl NodeGraphApp {
     pub fn new(cx: &CreationContext) -> Self {
-        let snarl = match cx.storage {
-            None => Snarl::new(),
-            Some(storage) => {
-                let snarl = storage
-                    .get_string("snarl")
-                    .and_then(|snarl| serde_json::from_str(&snarl).ok())
-                    .unwrap_or_else(Snarl::new);
-                snarl
-            }
+        let viewer = cx
+            .storage
+            .and_then(|storage| storage.get_string("viewer"))
+            .and_then(|viewer| serde_json::from_str(&viewer).ok())
+            .unwrap_or_default();
+
+        let path = cx
+            .storage
+            .and_then(|storage| storage.get_string("path"))
+            .and_then(|path| serde_json::from_str(&path).ok())
+            .unwrap_or_default();
+
+        let recent_files = cx
+            .storage
+            .and_then(|storage| storage.get_string("recent_files"))
+            .and_then(|recent| serde_json::from_str(&recent).ok())
+            .unwrap_or_default();
+
+        // The document used to be stored as a bare snarl and style
+        let stored = cx.storage.and_then(|storage| {
+            storage.get_string("document").or_else(|| {
+                let snarl = storage.get_string("snarl")?;
+                Some(match storage.get_string("style") {
+                    Some(style) => format!(r#"{{"snarl": {snarl}, "style": {style}}}"#),
+                    None => snarl,
+                })
+            })
+        });
+        let (document, unrestorable, file_error) = match stored {
+            None => (Document::default(), None, None),
+            Some(stored) => match Document::from_json(&stored) {
+                Ok(document) => (document, None, None),
+                Err(error) => (
+                    Document::default(),
+                    Some(stored),
+                    Some(format!(
+                        "Could not restore the last sketch, it is kept as a backup: {error:#}"
+                    )),
+                ),
+            },
         };
+        // Without a stored fingerprint, the restored document counts as saved
+        let fingerprint = document.fingerprint();
+        let saved = cx
+            .storage
+            .and_then(|storage| storage.get_string("saved"))
+            .and_then(|saved| saved.parse().ok())
+            .unwrap_or(fingerprint);
+
+        NodeGraphApp {
+            layout: layout(&document.snarl),
+            modified: saved != fingerprint,
+            document,
+            path,
+            saved,
+            recent_files,
+            pending: None,
+            file_error,
+            unrestorable,
+            title: String::new(),
+            solver: Solver::default(),
+            viewer,
+        }
+    }
+
+    fn has_unsaved_changes(&self) -> bool {
+        self.modified
+    }
+
+    /// Forgets about all changes, after the document was saved or replaced
+    fn mark_saved(&mut self) {
+        self.saved = self.document.fingerprint();
+        self.modified = false;
+        self.layout = layout(&self.document.snarl);
+    }
+
+    /// Passes the changes the user made during this frame on to the
+    /// solver, and notes if the document changed. This way neither has
+    /// to look at the whole document again on every frame
+    fn track_edits(&mut self, ctx: &egui::Context) {
+        let snarl = &self.document.snarl;
+        let mut changed = std::mem::take(&mut self.viewer.rewired);
+        for id in std::mem::take(&mut self.viewer.edited) {
+            if snarl.get_node(id).is_some() && self.solver.edited(snarl, id) {
+                changed = true;
+            }
+        }
+        let layout = layout(snarl);
+        if layout != self.layout {
+            // Ids of removed nodes are reused for new ones
+            for (id, _) in &layout {
+                if self.layout.binary_search_by_key(id, |(id, _)| *id).is_err() {
+                    self.solver.edited(snarl, *id);
+                }
+            }
+            changed = true;
+            self.layout = layout;
+        }
+        if changed {
+            self.modified = true;
+            // The results of the edit are only solved in the next frame
+            ctx.request_repaint();
+        }
+    }
+
+    /// Replaces the document right away, or asks what
+    /// should happen to unsaved changes first
+    fn request(&mut self, replace: Replace) {
+        if self.has_unsaved_changes() {
+            self.pending = Some(replace);
+        } else {
+            self.replace(replace);
+        }
+    }
+
+    fn replace(&mut self, replace: Replace) {
+        match replace {
+            Replace::New => {
+                self.document = Document::default();
+                self.path = None;
+                self.solver = Solver::default();
+                self.mark_saved();
+            }
+            Replace::Open(path) => {
+                let path = path.or_else(|| {
+                    rfd::FileDialog::new()
+                        .add_filter("recoded sketch", &[document::EXTENSION])
+                        .pick_file()
+                });
+                if let Some(path) = path {
+                    self.open(path);
+                }
+            }
+        }
+    }
+
+    fn open(&mut self, path: PathBuf) {
+        match Document::load(&path) {
+            Ok(document) => {
+                self.document = document;
+                self.solver = Solver::default();
+                self.mark_saved();
+                self.add_recent_file(&path);
+                self.path = Some(path);
+                self.file_error = None;
+            }
+            Err(error) => {
+                self.recent_files.retain(|recent| recent != &path);
+                self.file_error = Some(format!("Could not open {}: {error:#}", path.display()));
+            }
+        }
+    }
+
+    /// Saves to the current file, asking for one if the document
+    /// was never saved. Returns if the document was saved
+    fn save(&mut self) -> bool {
+        match self.path.clone() {
+            Some(path) => self.save_to(path),
+            None => self.save_as(),
+        }
+    }
+
+    fn save_as(&mut self) -> bool {
+        let file_name = format!("{}.{}", self.document_name(), document::EXTENSION);
+        let path = rfd::FileDialog::new()
+            .add_filter("recoded sketch", &[document::EXTENSION])
+            .set_file_name(file_name)
+            .save_file();
+        match path {
+            Some(path) if path.extension().is_none() => {
+                self.save_to(path.with_extension(document::EXTENSION))
+            }
+            Some(path) => self.save_to(path),
+            None => false,
+        }
+    }
 
-        let style = match cx.storage {
-            None => SnarlStyle::new(),
-            Some(storage) => {
-                let style = storage
-                    .get_string("style")
-                    .and_then(|style| serde_json::from_str(&style).ok())
-                    .unwrap_or_else(SnarlStyle::new);
-                style
+    fn save_to(&mut self, path: PathBuf) -> bool {
+        match self.document.save(&path) {
+            Ok(()) => {
+                self.mark_saved();
+                self.add_recent_file(&path);
+                self.path = Some(path);
+                self.file_error = None;
+                true
             }
+            Err(error) => {
+                self.file_error = Some(format!("Could not save {}: {error}", path.display()));
+                false
+            }
+        }
+    }
+
+    /// Writes the drawings of all canvases into a pdf, one per page
+    fn export_pdf(&mut self) {
+        let results = self.solver.solve(&self.document.snarl);
+        let pages = render::canvas_drawings(&self.document.snarl, results)
+            .into_iter()
+            .flat_map(|(_, drawings)| drawings)
+            .collect::<Vec<_>>();
+        if pages.is_empty() {
+            self.file_error = Some("There is no canvas with a drawing to export".to_string());
+            return;
+        }
+        let pdf = render::pdf::to_pdf(&pages);
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("pdf", &["pdf"])
+            .set_file_name(format!("{}.pdf", self.document_name()))
+            .save_file()
+        else {
+            return;
         };
+        self.file_error = std::fs::write(&path, pdf)
+            .err()
+            .map(|error| format!("Could not export {}: {error}", path.display()));
+    }
+
+    fn add_recent_file(&mut self, path: &Path) {
+        self.recent_files.retain(|recent| recent != path);
+        self.recent_files.insert(0, path.to_path_buf());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+
+    fn document_name(&self) -> String {
+        self.path
+            .as_ref()
+            .and_then(|path| path.file_stem())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Untitled".to_string())
+    }
+
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|input| input.consume_shortcut(&SAVE_AS_SHORTCUT)) {
+            self.save_as();
+        }
+        if ctx.input_mut(|input| input.consume_shortcut(&SAVE_SHORTCUT)) {
+            self.save();
+        }
+        if ctx.input_mut(|input| input.consume_shortcut(&OPEN_SHORTCUT)) {
+            self.request(Replace::Open(None));
+        }
+        if ctx.input_mut(|input| input.consume_shortcut(&NEW_SHORTCUT)) {
+            self.request(Replace::New);
+        }
+    }
+
+    fn show_menu_bar(&mut self, ctx: &egui::Context) {
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button("File", |ui| {
+                    if ui
+                        .add(
+                            egui::Button::new("New")
+                                .shortcut_text(ctx.format_shortcut(&NEW_SHORTCUT)),
+                        )
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.request(Replace::New);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new("Open…")
+                                .shortcut_text(ctx.format_shortcut(&OPEN_SHORTCUT)),
+                        )
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.request(Replace::Open(None));
+                    }
+                    ui.add_enabled_ui(!self.recent_files.is_empty(), |ui| {
+                        ui.menu_button("Open Recent", |ui| {
+                            for path in self.recent_files.clone() {
+                                if ui.button(path.display().to_string()).clicked() {
+                                    ui.close_menu();
+                                    self.request(Replace::Open(Some(path)));
+                                }
+                            }
+                        });
+                    });
+                    ui.separator();
+                    if ui
+                        .add(
+                            egui::Button::new("Save")
+                                .shortcut_text(ctx.format_shortcut(&SAVE_SHORTCUT)),
+                        )
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.save();
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new("Save As…")
+                                .shortcut_text(ctx.format_shortcut(&SAVE_AS_SHORTCUT)),
+                        )
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.save_as();
+                    }
+                    ui.separator();
+                    if ui.button("Export PDF…").clicked() {
+                        ui.close_menu();
+                        self.export_pdf();
+                    }
+                });
+            });
+        });
+    }
+
+    fn show_unsaved_changes_dialog(&mut self, ctx: &egui::Context) {
+        if self.pending.is_none() {
+            return;
+        }
+        egui::Window::new("Unsaved changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Do you want to save the changes to {}?",
+                    self.document_name()
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() && self.save() {
+                        if let Some(replace) = self.pending.take() {
+                            self.replace(replace);
+                        }
+                    }
+                    if ui.button("Discard").clicked() {
+                        if let Some(replace) = self.pending.take() {
+                            self.replace(replace);
+                        }
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.pending = None;
+                    }
+                });
+            });
+    }
+
+    /// Lists the parameters of the graph, so they can be
+    /// tweaked without searching for their nodes
+    fn show_parameters(&mut self, ctx: &egui::Context) {
+        let interface = Interface::of(&self.document.snarl);
+        if interface.parameters.is_empty() {
+            return;
+        }
+        if ctx.input(viewer::editing) {
+            self.viewer
+                .edited
+                .extend(interface.parameters.iter().map(|parameter| parameter.node));
+        }
+        egui::SidePanel::right("parameters").show(ctx, |ui| {
+            ui.heading("Parameters");
+            egui::Grid::new("parameters").show(ui, |ui| {
+                for parameter in &interface.parameters {
+                    let label = ui.label(&parameter.name);
+                    if !parameter.description.is_empty() {
+                        label.on_hover_text(&parameter.description);
+                    }
+                    if let Some(Nodes::Parameter(node)) =
+                        self.document.snarl.get_node_mut(parameter.node)
+                    {
+                        ui.horizontal(|ui| node.show_value_editor(ui));
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    }
 
-        NodeGraphApp { snarl, style }
+    /// Shows the file name in the window title, marking unsaved changes
+    fn update_title(&mut self, ctx: &egui::Context) {
+        let marker = if self.has_unsaved_changes() { "*" } else { "" };
+        let title = format!("{}{marker} - recoded", self.document_name());
+        if title != self.title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.title = title;
+        }
     }
 }
 
 impl App for NodeGraphApp {
-    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         egui_extras::install_image_loaders(ctx);
 
+        // Pressing anywhere but on a node or a popup of it ends its editing
+        let pressed_elsewhere = ctx.input(|input| {
+            input.pointer.any_pressed()
+                && input.pointer.press_origin().is_some_and(|origin| {
+                    ctx.layer_id_at(origin)
+                        .is_none_or(|layer| layer.order != egui::Order::Foreground)
+                })
+        });
+        if pressed_elsewhere {
+            self.viewer.active = None;
+        }
+
+        self.handle_shortcuts(ctx);
+        self.show_menu_bar(ctx);
+        self.show_unsaved_changes_dialog(ctx);
+        self.show_parameters(ctx);
+
+        let results = self.solver.solve(&self.document.snarl);
+        let messages = [
+            &self.viewer.rejected,
+            &self.viewer.converted,
+            &self.viewer.export_error,
+            &self.file_error,
+        ];
+        if messages.iter().any(|message| message.is_some()) {
+            egui::TopBottomPanel::bottom("messages").show(ctx, |ui| {
+                for message in messages.into_iter().flatten() {
+                    ui.colored_label(ui.visuals().warn_fg_color, message);
+                }
+            });
+        }
         egui::CentralPanel::default().show(ctx, |ui| {
-            self.snarl.show(
-                &mut NodeGraphViewer,
-                &self.style,
+            self.document.snarl.show(
+                &mut NodeGraphViewer::new(results, &mut self.viewer),
+                &self.document.style,
                 egui::Id::new("snarl"),
                 ui,
             );
         });
+
+        self.track_edits(ctx);
+        self.update_title(ctx);
     }
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        let snarl = serde_json::to_string(&self.snarl).unwrap();
-        storage.set_string("snarl", snarl);
+        let document = self.document.to_json().unwrap();
+        storage.set_string("document", document);
+
+        if let Some(unrestorable) = &self.unrestorable {
+            storage.set_string("document_backup", unrestorable.clone());
+        }
+
+        let viewer = serde_json::to_string(&self.viewer).unwrap();
+        storage.set_string("viewer", viewer);
 
-        let style = serde_json::to_string(&self.style).unwrap();
-        storage.set_string("style", style);
+        let path = serde_json::to_string(&self.path).unwrap();
+        storage.set_string("path", path);
+
+        let recent_files = serde_json::to_string(&self.recent_files).unwrap();
+        storage.set_string("recent_files", recent_files);
+
+        storage.set_string("saved", self.saved.to_string());
     }
 }
+
+/// Every node and its position, sorted by node
+fn layout(snarl: &Snarl<Nodes>) -> Vec<(NodeId, egui::Pos2)> {
+    let mut layout = snarl
+        .nodes_pos_ids()
+        .map(|(id, pos, _)| (id, pos))
+        .collect::<Vec<_>>();
+    layout.sort_by_key(|(id, _)| *id);
+    layout
+}
@@ -0,0 +1,251 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context};
+use egui_snarl::{InPinId, NodeId, Snarl};
+
+use crate::{
+    color::Color,
+    document::Document,
+    interface::{self, Interface},
+    nodes::Nodes,
+    render::{self, suffixed, ExportOptions, Format, Plot, PlotOptions, RasterOptions},
+    schema,
+    solver::Solver,
+    value::Value,
+};
+
+#[derive(clap::Parser)]
+#[command(version, about = "A node editor for generative art")]
+pub struct Cli {
+    /// Opens the editor if no command is given
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(clap::Subcommand)]
+pub enum Command {
+    /// Solves a sketch without opening the editor,
+    /// and writes the drawing of every canvas as svg
+    Render(RenderArgs),
+    /// Serves a directory of sketches over http, so they
+    /// can be solved with parameters sent as json
+    Serve(ServeArgs),
+    /// Prints the parameters and outputs of a sketch as json
+    Interface {
+        /// The `.recoded` sketch to inspect
+        graph: PathBuf,
+        /// Prints the JSON Schema of the parameters instead
+        #[arg(long)]
+        schema: bool,
+    },
+}
+
+#[derive(clap::Args)]
+pub struct RenderArgs {
+    /// The `.recoded` sketch to render
+    pub graph: PathBuf,
+    /// Json object with values for parameters, keyed by parameter name,
+    /// or replacing node inputs, keyed by node id and input index like `"3:1"`
+    #[arg(long)]
+    pub inputs: Option<PathBuf>,
+    /// Where to write the drawing, as svg, png, pdf, gcode or hpgl depending
+    /// on the extension. If there is more than one drawing, the node id and drawing
+    /// index are appended to the file name, except for pdf which gets a page
+    /// for every drawing
+    #[arg(long, short)]
+    pub out: PathBuf,
+    /// Pixels per unit of the canvas size, only used for png,
+    /// which may have up to 64 megapixels
+    #[arg(long, default_value_t = 1.0)]
+    pub scale: f64,
+    /// Hex color filling png images, or `none` to keep them transparent
+    #[arg(long, default_value = "#ffffff", value_parser = parse_background)]
+    pub background: Background,
+    #[command(flatten)]
+    pub plot: PlotArgs,
+}
+
+/// Settings for pen plotters, only used for gcode and hpgl
+#[derive(clap::Args)]
+pub struct PlotArgs {
+    /// Speed while drawing, in mm per minute
+    #[arg(long)]
+    pub feed_rate: Option<f64>,
+    /// Speed while the pen is up, in mm per minute
+    #[arg(long)]
+    pub travel_rate: Option<f64>,
+    /// G-code lifting the pen
+    #[arg(long)]
+    pub pen_up: Option<String>,
+    /// G-code lowering the pen
+    #[arg(long)]
+    pub pen_down: Option<String>,
+}
+
+impl PlotArgs {
+    fn options(&self) -> PlotOptions {
+        let defaults = PlotOptions::default();
+        PlotOptions {
+            feed_rate: self.feed_rate.unwrap_or(defaults.feed_rate),
+            travel_rate: self.travel_rate.unwrap_or(defaults.travel_rate),
+            pen_up: self.pen_up.clone().unwrap_or(defaults.pen_up),
+            pen_down: self.pen_down.clone().unwrap_or(defaults.pen_down),
+            ..defaults
+        }
+    }
+}
+
+/// Wrapper, so clap doesn't treat the option as optional
+#[derive(Clone, Copy)]
+pub struct Background(pub Option<Color>);
+
+fn parse_background(text: &str) -> Result<Background, String> {
+    match text {
+        "none" | "transparent" => Ok(Background(None)),
+        hex => Color::from_hex(hex)
+            .map(|color| Background(Some(color)))
+            .ok_or_else(|| format!("'{hex}' is not a hex color like #ff8800")),
+    }
+}
+
+#[derive(clap::Args)]
+pub struct ServeArgs {
+    /// The directory with the `.recoded` sketches to serve
+    pub dir: PathBuf,
+    #[arg(long, default_value = "127.0.0.1:3000")]
+    pub address: SocketAddr,
+}
+
+pub fn render(args: &RenderArgs) -> anyhow::Result<()> {
+    let format = Format::from_path(&args.out)?;
+    let options = ExportOptions {
+        raster: RasterOptions {
+            scale: args.scale,
+            background: args.background.0,
+        },
+        plot: args.plot.options(),
+    };
+    let mut document = load(&args.graph)?;
+    let overrides = match &args.inputs {
+        None => HashMap::new(),
+        Some(path) => {
+            let json = std::fs::read_to_string(path)
+                .with_context(|| format!("Could not read {}", path.display()))?;
+            parse_inputs(&json, &mut document.snarl)
+                .with_context(|| format!("Invalid inputs in {}", path.display()))?
+        }
+    };
+
+    let mut solver = Solver::with_overrides(overrides);
+    let results = solver.solve(&document.snarl);
+
+    let mut nodes = document.snarl.node_ids().collect::<Vec<_>>();
+    nodes.sort_by_key(|(id, _)| *id);
+    for (id, node) in &nodes {
+        if let Some(error) = results.error(*id) {
+            eprintln!("{} {}: {error}", node.title(), id.0);
+        }
+    }
+
+    let drawings = render::canvas_drawings(&document.snarl, results);
+    if drawings.is_empty() {
+        bail!("The sketch has no canvas to render");
+    }
+    let count = drawings
+        .iter()
+        .map(|(_, drawings)| drawings.len())
+        .sum::<usize>();
+    if count == 0 {
+        bail!("None of the canvases could be drawn");
+    }
+
+    if format == Format::Pdf {
+        let pages = drawings
+            .iter()
+            .flat_map(|(_, drawings)| drawings.iter().copied())
+            .collect::<Vec<_>>();
+        std::fs::write(&args.out, render::pdf::to_pdf(&pages))
+            .with_context(|| format!("Could not write {}", args.out.display()))?;
+        println!("Wrote {} with {count} pages", args.out.display());
+    } else {
+        for (id, node_drawings) in &drawings {
+            for (index, drawing) in node_drawings.iter().enumerate() {
+                let path = match (count, node_drawings.len()) {
+                    (1, _) => args.out.clone(),
+                    (_, 1) => suffixed(&args.out, &format!("{}", id.0)),
+                    _ => suffixed(&args.out, &format!("{}-{index}", id.0)),
+                };
+                std::fs::write(&path, drawing.export(format, &options)?)
+                    .with_context(|| format!("Could not write {}", path.display()))?;
+                println!("Wrote {}", path.display());
+                if format.is_plot() {
+                    println!("{}", Plot::new(drawing, &options.plot).report());
+                }
+            }
+        }
+    }
+
+    let interface = Interface::of(&document.snarl);
+    for (output, value) in interface.output_values(results) {
+        match value {
+            Some(value) => println!("{} = {value}", output.name),
+            None => println!("{} failed", output.name),
+        }
+    }
+
+    Ok(())
+}
+
+pub fn print_interface(graph: &Path, schema: bool) -> anyhow::Result<()> {
+    let document = load(graph)?;
+    let interface = Interface::of(&document.snarl);
+    let json = if schema {
+        let name = graph.file_stem().unwrap_or_default().to_string_lossy();
+        schema::parameters_schema(&name, &interface)
+    } else {
+        serde_json::to_value(&interface)?
+    };
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+fn load(graph: &Path) -> anyhow::Result<Document> {
+    Document::load(graph).with_context(|| format!("Could not load {}", graph.display()))
+}
+
+/// Reads inputs from a json object like `{"count": 5, "3:1": 5.0}`,
+/// see [`Value::from_json`] for how values are written. Parameters
+/// are set in the graph, the values for input pins are returned
+fn parse_inputs(json: &str, snarl: &mut Snarl<Nodes>) -> anyhow::Result<HashMap<InPinId, Value>> {
+    let json: HashMap<String, serde_json::Value> = serde_json::from_str(json)?;
+    let mut parameters = HashMap::new();
+    let mut overrides = HashMap::new();
+    for (key, value) in &json {
+        let value = Value::from_json(value).with_context(|| format!("Invalid value for {key}"))?;
+        if key.contains(':') {
+            overrides.insert(parse_pin(key, snarl)?, value);
+        } else {
+            parameters.insert(key.clone(), value);
+        }
+    }
+    interface::set_parameters(snarl, &parameters)?;
+    Ok(overrides)
+}
+
+fn parse_pin(key: &str, snarl: &Snarl<Nodes>) -> anyhow::Result<InPinId> {
+    let invalid = || anyhow!("'{key}' is not a pin, it has to look like 'node:input'");
+    let (node, input) = key.split_once(':').ok_or_else(invalid)?;
+    let node = NodeId(node.trim().parse().map_err(|_| invalid())?);
+    let input = input.trim().parse().map_err(|_| invalid())?;
+    let Some(inputs) = snarl.get_node(node).map(Nodes::inputs) else {
+        bail!("There is no node {}", node.0);
+    };
+    if input >= inputs {
+        bail!("Node {} has no input {input}", node.0);
+    }
+    Ok(InPinId { node, input })
+}
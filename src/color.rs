@@ -0,0 +1,274 @@
+/// A color in the sRGB color space, with all channels
+/// stored as numbers between 0 and 1
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Self::BLACK
+    }
+}
+
+impl Color {
+    pub const BLACK: Self = Self::rgb(0.0, 0.0, 0.0);
+    pub const WHITE: Self = Self::rgb(1.0, 1.0, 1.0);
+
+    pub const fn rgb(r: f64, g: f64, b: f64) -> Self {
+        Self { r, g, b, a: 1.0 }
+    }
+
+    pub const fn rgba(r: f64, g: f64, b: f64, a: f64) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub fn to_piet(self) -> piet::Color {
+        piet::Color::rgba(self.r, self.g, self.b, self.a)
+    }
+
+    pub fn to_egui(self) -> egui::Color32 {
+        let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        egui::Color32::from_rgba_unmultiplied(
+            channel(self.r),
+            channel(self.g),
+            channel(self.b),
+            channel(self.a),
+        )
+    }
+
+    /// Reads colors like `#ff8800` or `#ff880080`, the `#` is optional
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim().trim_start_matches('#');
+        if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |index: usize| {
+            let channel = hex.get(index * 2..index * 2 + 2)?;
+            u8::from_str_radix(channel, 16)
+                .ok()
+                .map(|channel| channel as f64 / 255.0)
+        };
+        let a = if hex.len() == 8 { channel(3)? } else { 1.0 };
+        Some(Self::rgba(channel(0)?, channel(1)?, channel(2)?, a))
+    }
+
+    pub fn to_hex(self) -> String {
+        let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        format!(
+            "#{:02x}{:02x}{:02x}{:02x}",
+            channel(self.r),
+            channel(self.g),
+            channel(self.b),
+            channel(self.a)
+        )
+    }
+
+    /// Hue in degrees, saturation and value between 0 and 1
+    pub fn from_hsv(hue: f64, saturation: f64, value: f64) -> Self {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = value - chroma;
+        Self::rgb(r + m, g + m, b + m)
+    }
+
+    /// Lightness between 0 and 1, chroma from 0 to about 0.4 and hue in
+    /// degrees. Colors outside of sRGB lose chroma until they fit
+    pub fn from_oklch(lightness: f64, chroma: f64, hue: f64) -> Self {
+        let color = Oklab::from_lch(lightness, chroma, hue).to_color(1.0);
+        if color.in_gamut() {
+            return color;
+        }
+        let (mut low, mut high) = (0.0, chroma);
+        for _ in 0..24 {
+            let middle = (low + high) / 2.0;
+            if Oklab::from_lch(lightness, middle, hue)
+                .to_color(1.0)
+                .in_gamut()
+            {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        Oklab::from_lch(lightness, low, hue).to_color(1.0).clamped()
+    }
+
+    pub fn with_alpha(self, a: f64) -> Self {
+        Self { a, ..self }
+    }
+
+    pub fn in_gamut(self) -> bool {
+        const EPSILON: f64 = 1e-6;
+        [self.r, self.g, self.b]
+            .iter()
+            .all(|channel| (-EPSILON..=1.0 + EPSILON).contains(channel))
+    }
+
+    pub fn clamped(self) -> Self {
+        Self::rgba(
+            self.r.clamp(0.0, 1.0),
+            self.g.clamp(0.0, 1.0),
+            self.b.clamp(0.0, 1.0),
+            self.a.clamp(0.0, 1.0),
+        )
+    }
+
+    /// Blends two colors, `t` going from 0 for `self` to 1 for `other`
+    pub fn mix(self, other: Self, t: f64, space: Interpolation) -> Self {
+        let alpha = lerp(self.a, other.a, t);
+        match space {
+            Interpolation::Srgb => Self::rgba(
+                lerp(self.r, other.r, t),
+                lerp(self.g, other.g, t),
+                lerp(self.b, other.b, t),
+                alpha,
+            ),
+            Interpolation::Oklab => {
+                let (from, to) = (Oklab::from_color(self), Oklab::from_color(other));
+                Oklab {
+                    l: lerp(from.l, to.l, t),
+                    a: lerp(from.a, to.a, t),
+                    b: lerp(from.b, to.b, t),
+                }
+                .to_color(alpha)
+                .clamped()
+            }
+            Interpolation::Oklch => {
+                let (from, to) = (
+                    Oklab::from_color(self).to_lch(),
+                    Oklab::from_color(other).to_lch(),
+                );
+                // Gray has no hue of its own, so it takes the hue of the other color
+                let (from_hue, to_hue) = match (from.1 < ACHROMATIC, to.1 < ACHROMATIC) {
+                    (true, false) => (to.2, to.2),
+                    (false, true) => (from.2, from.2),
+                    _ => (from.2, to.2),
+                };
+                // Along the shorter way around the hue circle
+                let delta = (to_hue - from_hue + 180.0).rem_euclid(360.0) - 180.0;
+                Self::from_oklch(
+                    lerp(from.0, to.0, t),
+                    lerp(from.1, to.1, t),
+                    from_hue + delta * t,
+                )
+                .with_alpha(alpha)
+            }
+        }
+    }
+
+    /// Lightness, chroma and hue in degrees, see [`Color::from_oklch`]
+    pub fn to_oklch(self) -> (f64, f64, f64) {
+        Oklab::from_color(self).to_lch()
+    }
+}
+
+/// Chroma below which colors count as gray
+const ACHROMATIC: f64 = 1e-4;
+
+/// The space colors are blended in
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Perceptually uniform, so steps of the same size look alike
+    #[default]
+    Oklab,
+    /// Like Oklab, but blends the hue around the color wheel
+    Oklch,
+    /// Blends the channels directly, which dulls the colors in between
+    Srgb,
+}
+
+impl Interpolation {
+    pub const ALL: [Self; 3] = [Self::Oklab, Self::Oklch, Self::Srgb];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Oklab => "Oklab",
+            Self::Oklch => "Oklch",
+            Self::Srgb => "sRGB",
+        }
+    }
+}
+
+fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from + (to - from) * t
+}
+
+fn to_linear(channel: f64) -> f64 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(channel: f64) -> f64 {
+    if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// The Oklab color space by Björn Ottosson, see
+/// <https://bottosson.github.io/posts/oklab/>
+#[derive(Debug, Clone, Copy)]
+struct Oklab {
+    l: f64,
+    a: f64,
+    b: f64,
+}
+
+impl Oklab {
+    fn from_color(color: Color) -> Self {
+        let (r, g, b) = (to_linear(color.r), to_linear(color.g), to_linear(color.b));
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+        Self {
+            l: 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            a: 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            b: 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        }
+    }
+
+    /// The color in sRGB, which may lie outside of its gamut
+    fn to_color(self, alpha: f64) -> Color {
+        let l = (self.l + 0.3963377774 * self.a + 0.2158037573 * self.b).powi(3);
+        let m = (self.l - 0.1055613458 * self.a - 0.0638541728 * self.b).powi(3);
+        let s = (self.l - 0.0894841775 * self.a - 1.2914855480 * self.b).powi(3);
+        Color::rgba(
+            from_linear(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+            from_linear(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+            from_linear(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+            alpha,
+        )
+    }
+
+    fn from_lch(lightness: f64, chroma: f64, hue: f64) -> Self {
+        let hue = hue.to_radians();
+        Self {
+            l: lightness,
+            a: chroma * hue.cos(),
+            b: chroma * hue.sin(),
+        }
+    }
+
+    fn to_lch(self) -> (f64, f64, f64) {
+        let chroma = self.a.hypot(self.b);
+        let hue = self.b.atan2(self.a).to_degrees().rem_euclid(360.0);
+        (self.l, chroma, hue)
+    }
+}
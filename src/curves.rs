@@ -0,0 +1,228 @@
+//! Smooth curves through or along points, all converted to
+//! cubic or quadratic Bezier segments so every renderer can draw them
+
+use piet::kurbo::{BezPath, Point, Vec2};
+
+use crate::solver::EvalError;
+
+/// The degree of the segments of a Bezier curve
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BezierDegree {
+    Quadratic,
+    #[default]
+    Cubic,
+}
+
+impl BezierDegree {
+    pub const ALL: [Self; 2] = [Self::Quadratic, Self::Cubic];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quadratic => "Quadratic",
+            Self::Cubic => "Cubic",
+        }
+    }
+
+    /// Control points every segment adds to the one it starts from
+    fn points_per_segment(self) -> usize {
+        match self {
+            Self::Quadratic => 2,
+            Self::Cubic => 3,
+        }
+    }
+}
+
+/// Chains Bezier segments, where the last point of a segment is
+/// the first of the next. Cubic curves need 4, 7, 10… points
+pub fn bezier(points: &[Point], degree: BezierDegree) -> Result<BezPath, EvalError> {
+    let step = degree.points_per_segment();
+    let [first, rest @ ..] = points else {
+        return Err(EvalError::Domain(
+            "A Bezier curve needs control points".to_string(),
+        ));
+    };
+    if rest.is_empty() || rest.len() % step != 0 {
+        return Err(EvalError::Domain(format!(
+            "A {} Bezier curve needs {} points, plus {step} for every further segment, but got {}",
+            degree.name().to_lowercase(),
+            step + 1,
+            points.len()
+        )));
+    }
+    let mut path = BezPath::new();
+    path.move_to(*first);
+    for segment in rest.chunks_exact(step) {
+        match degree {
+            BezierDegree::Quadratic => path.quad_to(segment[0], segment[1]),
+            BezierDegree::Cubic => path.curve_to(segment[0], segment[1], segment[2]),
+        }
+    }
+    Ok(path)
+}
+
+/// Catmull-Rom spline through all points. An `alpha` of 0 gives the
+/// uniform, 0.5 the centripetal and 1 the chordal variant, where the
+/// centripetal one never forms cusps or loops within a segment
+pub fn catmull_rom(points: &[Point], alpha: f64, closed: bool) -> Result<BezPath, EvalError> {
+    let points = distinct(points, closed, 2)?;
+    let count = points.len();
+    let at = |index: isize| -> Point {
+        if closed {
+            points[index.rem_euclid(count as isize) as usize]
+        } else if index < 0 {
+            // Mirrored neighbours let the open ends run straight out
+            points[0] + (points[0] - points[1])
+        } else if index as usize >= count {
+            points[count - 1] + (points[count - 1] - points[count - 2])
+        } else {
+            points[index as usize]
+        }
+    };
+
+    let mut path = BezPath::new();
+    path.move_to(points[0]);
+    let segments = if closed { count } else { count - 1 };
+    for index in 0..segments as isize {
+        let (p0, p1, p2, p3) = (at(index - 1), at(index), at(index + 1), at(index + 2));
+        let d1 = p0.distance(p1).powf(alpha);
+        let d2 = p1.distance(p2).powf(alpha);
+        let d3 = p2.distance(p3).powf(alpha);
+        let c1 = (p2.to_vec2() * (d1 * d1) - p0.to_vec2() * (d2 * d2)
+            + p1.to_vec2() * (2.0 * d1 * d1 + 3.0 * d1 * d2 + d2 * d2))
+            / (3.0 * d1 * (d1 + d2));
+        let c2 = (p1.to_vec2() * (d3 * d3) - p3.to_vec2() * (d2 * d2)
+            + p2.to_vec2() * (2.0 * d3 * d3 + 3.0 * d3 * d2 + d2 * d2))
+            / (3.0 * d3 * (d3 + d2));
+        path.curve_to(c1.to_point(), c2.to_point(), p2);
+    }
+    if closed {
+        path.close_path();
+    }
+    Ok(path)
+}
+
+/// Cubic spline through all points which is smooth up to its curvature,
+/// with straight ends when open. Every point is hit at an equal step
+/// of the curve parameter
+pub fn interpolating(points: &[Point], closed: bool) -> Result<BezPath, EvalError> {
+    let points = distinct(points, closed, if closed { 3 } else { 2 })?;
+    let count = points.len();
+    let k = |index: usize| points[index % count].to_vec2();
+
+    // The first control point of every segment, from the equations
+    // demanding equal first and second derivatives at every point
+    let first_controls = if closed {
+        let rhs = (0..count)
+            .map(|i| k(i) * 4.0 + k(i + 1) * 2.0)
+            .collect::<Vec<_>>();
+        solve_cyclic(&rhs)
+    } else {
+        let segments = count - 1;
+        if segments == 1 {
+            vec![k(0).lerp(k(1), 1.0 / 3.0)]
+        } else {
+            let mut lower = vec![1.0; segments];
+            let mut diagonal = vec![4.0; segments];
+            let upper = vec![1.0; segments];
+            let mut rhs = (0..segments)
+                .map(|i| k(i) * 4.0 + k(i + 1) * 2.0)
+                .collect::<Vec<_>>();
+            diagonal[0] = 2.0;
+            rhs[0] = k(0) + k(1) * 2.0;
+            lower[segments - 1] = 2.0;
+            diagonal[segments - 1] = 7.0;
+            rhs[segments - 1] = k(segments - 1) * 8.0 + k(segments);
+            solve_tridiagonal(&lower, &diagonal, &upper, &rhs)
+        }
+    };
+
+    let mut path = BezPath::new();
+    path.move_to(points[0]);
+    let segments = first_controls.len();
+    for (index, first) in first_controls.iter().enumerate() {
+        let second = if closed || index + 1 < segments {
+            k(index + 1) * 2.0 - first_controls[(index + 1) % segments]
+        } else {
+            (k(index + 1) + *first) / 2.0
+        };
+        path.curve_to(
+            first.to_point(),
+            second.to_point(),
+            points[(index + 1) % count],
+        );
+    }
+    if closed {
+        path.close_path();
+    }
+    Ok(path)
+}
+
+/// The points without consecutive duplicates, which have no direction
+/// between them. Errors if fewer than `minimum` points remain
+fn distinct(points: &[Point], closed: bool, minimum: usize) -> Result<Vec<Point>, EvalError> {
+    let mut distinct: Vec<Point> = Vec::with_capacity(points.len());
+    for point in points {
+        if distinct
+            .last()
+            .is_none_or(|last| last.distance(*point) > 1e-9)
+        {
+            distinct.push(*point);
+        }
+    }
+    if closed && distinct.len() > 1 && distinct[0].distance(distinct[distinct.len() - 1]) <= 1e-9 {
+        distinct.pop();
+    }
+    if distinct.len() < minimum {
+        return Err(EvalError::Domain(format!(
+            "The curve needs at least {minimum} distinct points, but got {}",
+            distinct.len()
+        )));
+    }
+    Ok(distinct)
+}
+
+/// Thomas algorithm for tridiagonal systems, `lower[0]` and
+/// the last entry of `upper` are ignored
+fn solve_tridiagonal(lower: &[f64], diagonal: &[f64], upper: &[f64], rhs: &[Vec2]) -> Vec<Vec2> {
+    let n = rhs.len();
+    let mut upper_prime = vec![0.0; n];
+    let mut rhs_prime = vec![Vec2::ZERO; n];
+    upper_prime[0] = upper[0] / diagonal[0];
+    rhs_prime[0] = rhs[0] / diagonal[0];
+    for i in 1..n {
+        let denominator = diagonal[i] - lower[i] * upper_prime[i - 1];
+        upper_prime[i] = upper[i] / denominator;
+        rhs_prime[i] = (rhs[i] - rhs_prime[i - 1] * lower[i]) / denominator;
+    }
+    let mut solution = vec![Vec2::ZERO; n];
+    solution[n - 1] = rhs_prime[n - 1];
+    for i in (0..n - 1).rev() {
+        solution[i] = rhs_prime[i] - solution[i + 1] * upper_prime[i];
+    }
+    solution
+}
+
+/// Solves the cyclic system with 4 on the diagonal and 1 on both
+/// neighbouring diagonals wrapping around, by Sherman-Morrison
+fn solve_cyclic(rhs: &[Vec2]) -> Vec<Vec2> {
+    let n = rhs.len();
+    let gamma = -4.0;
+    let lower = vec![1.0; n];
+    let upper = vec![1.0; n];
+    let mut diagonal = vec![4.0; n];
+    diagonal[0] -= gamma;
+    diagonal[n - 1] -= 1.0 / gamma;
+
+    let x = solve_tridiagonal(&lower, &diagonal, &upper, rhs);
+    let mut u = vec![Vec2::ZERO; n];
+    u[0] = Vec2::new(gamma, gamma);
+    u[n - 1] = Vec2::new(1.0, 1.0);
+    let z = solve_tridiagonal(&lower, &diagonal, &upper, &u);
+
+    // The correction is the same for both coordinates, as is z
+    let factor = (x[0] + x[n - 1] / gamma) / (1.0 + z[0].x + z[n - 1].x / gamma);
+    x.iter()
+        .zip(&z)
+        .map(|(x, z)| Vec2::new(x.x - factor.x * z.x, x.y - factor.y * z.y))
+        .collect()
+}
@@ -0,0 +1,164 @@
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+use egui_snarl::{ui::SnarlStyle, Snarl};
+use serde_json::{json, Value as Json};
+
+use crate::nodes::Nodes;
+
+/// File extension of saved sketches
+pub const EXTENSION: &str = "recoded";
+
+/// Marks a json file as a sketch, in case the extension got lost
+const FORMAT: &str = "recoded";
+
+/// Version of the documents written by this build.
+///
+/// Every change to the serialized form of a node has to bump this
+/// and add a migration to [`MIGRATIONS`], which upgrades documents
+/// of the previous version. Documents are only ever migrated as json,
+/// so old node structs don't have to be kept around.
+pub const VERSION: u32 = 2;
+
+type Migration = fn(Json) -> anyhow::Result<Json>;
+
+/// The migration at index `n` upgrades a document from version `n` to `n + 1`
+const MIGRATIONS: [Migration; VERSION as usize] = [migrate_v0, migrate_v1];
+
+/// A sketch, as it is stored in a `.recoded` file
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Document {
+    pub snarl: Snarl<Nodes>,
+    pub style: SnarlStyle,
+}
+
+/// What actually gets written to disk, so the
+/// version is known when reading the document again
+#[derive(serde::Serialize)]
+struct Envelope<'a> {
+    format: &'static str,
+    version: u32,
+    document: &'a Document,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self {
+            snarl: Snarl::new(),
+            style: SnarlStyle::new(),
+        }
+    }
+}
+
+impl Document {
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Reads a document of any version, migrating it to the current one
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let json: Json = serde_json::from_str(json)?;
+        let (version, mut document) = unwrap_envelope(json)?;
+        if version > VERSION {
+            bail!(
+                "The sketch was saved by a newer version of recoded \
+                (format version {version}, but only {VERSION} is supported)"
+            );
+        }
+        for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            document = migration(document)
+                .with_context(|| format!("Failed to migrate from format version {from}"))?;
+        }
+        Ok(serde_json::from_value(document)?)
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        let envelope = Envelope {
+            format: FORMAT,
+            version: VERSION,
+            document: self,
+        };
+        Ok(serde_json::to_string_pretty(&envelope)?)
+    }
+
+    /// Changes whenever anything in the document changes, used to find
+    /// out if there are unsaved changes. It is stored between runs,
+    /// so unlike the hashers of std it stays the same across releases
+    pub fn fingerprint(&self) -> u64 {
+        let mut json = serde_json::to_value(self).expect("Documents can always be serialized");
+        // Wires are stored in a set, so their order differs between loads
+        if let Some(Json::Array(wires)) = json.pointer_mut("/snarl/wires") {
+            wires.sort_by_cached_key(|wire| wire.to_string());
+        }
+        fnv1a(json.to_string().as_bytes())
+    }
+}
+
+/// The 64 bit FNV-1a hash, which is fully specified
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Splits a file into the format version and the document stored in it.
+/// Files from before the envelope existed are recognized by their shape
+fn unwrap_envelope(json: Json) -> anyhow::Result<(u32, Json)> {
+    let Json::Object(mut object) = json else {
+        bail!("A sketch has to be a json object");
+    };
+    if let Some(version) = object.remove("version") {
+        if object.get("format").and_then(Json::as_str) != Some(FORMAT) {
+            bail!("Not a recoded sketch");
+        }
+        let version = version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| anyhow!("Invalid format version {version}"))?;
+        let document = object
+            .remove("document")
+            .ok_or_else(|| anyhow!("The sketch has no document"))?;
+        Ok((version, document))
+    } else if object.contains_key("snarl") {
+        Ok((1, Json::Object(object)))
+    } else if object.contains_key("nodes") {
+        Ok((0, Json::Object(object)))
+    } else {
+        bail!("Not a recoded sketch")
+    }
+}
+
+/// Version 0 was a bare snarl without the style, as it was kept in the app
+/// storage. Nodes matching lists were introduced with version 1, the
+/// matching of older nodes is filled in by its serde default
+fn migrate_v0(snarl: Json) -> anyhow::Result<Json> {
+    Ok(json!({
+        "snarl": snarl,
+        "style": serde_json::to_value(SnarlStyle::new())?,
+    }))
+}
+
+/// Version 2 introduced the envelope, the document itself is unchanged
+fn migrate_v1(document: Json) -> anyhow::Result<Json> {
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprints_use_the_specified_hash() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+}
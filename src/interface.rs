@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context};
+use egui_snarl::{NodeId, OutPinId, Snarl};
+
+use crate::{
+    nodes::Nodes,
+    solver::Results,
+    value::{Value, ValueType},
+};
+
+/// A named input of a graph, declared by a parameter node
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct Parameter {
+    #[serde(skip)]
+    pub node: NodeId,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: ValueType,
+    #[serde(serialize_with = "plain_json")]
+    pub default: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
+
+/// A named result of a graph, declared by an output node
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct Output {
+    #[serde(skip)]
+    pub node: NodeId,
+    pub name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
+
+/// Everything a graph exposes to the outside, so it
+/// can be used without knowing how it is built
+#[derive(serde::Serialize, Debug, Clone, Default)]
+pub struct Interface {
+    pub parameters: Vec<Parameter>,
+    pub outputs: Vec<Output>,
+}
+
+impl Interface {
+    /// Collects the parameter and output nodes, ordered by node id
+    pub fn of(snarl: &Snarl<Nodes>) -> Self {
+        let mut nodes = snarl.node_ids().collect::<Vec<_>>();
+        nodes.sort_by_key(|(id, _)| *id);
+
+        let mut interface = Self::default();
+        for (id, node) in nodes {
+            match node {
+                Nodes::Parameter(parameter) => interface.parameters.push(Parameter {
+                    node: id,
+                    name: parameter.name.clone(),
+                    kind: parameter.kind,
+                    default: parameter.default.clone(),
+                    min: parameter.min,
+                    max: parameter.max,
+                    description: parameter.description.clone(),
+                }),
+                Nodes::Output(output) => interface.outputs.push(Output {
+                    node: id,
+                    name: output.name.clone(),
+                    description: output.description.clone(),
+                }),
+                _ => {}
+            }
+        }
+        interface
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<&Parameter> {
+        self.parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+    }
+
+    /// The default value of every parameter, keyed by name
+    pub fn defaults(&self) -> HashMap<String, Value> {
+        self.parameters
+            .iter()
+            .map(|parameter| (parameter.name.clone(), parameter.default.clone()))
+            .collect()
+    }
+
+    /// The solved value of every output, `None` if the output failed
+    pub fn output_values<'a>(
+        &'a self,
+        results: &'a Results,
+    ) -> impl Iterator<Item = (&'a Output, Option<&'a Value>)> {
+        self.outputs.iter().map(|output| {
+            let value = results.get(OutPinId {
+                node: output.node,
+                output: 0,
+            });
+            (output, value)
+        })
+    }
+}
+
+/// Replaces the values of the parameters with the given names.
+/// Every parameter sharing a name is set, and values
+/// are checked against the type and range of the parameter
+pub fn set_parameters(
+    snarl: &mut Snarl<Nodes>,
+    values: &HashMap<String, Value>,
+) -> anyhow::Result<()> {
+    let interface = Interface::of(snarl);
+    let mut names = values.keys().collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        if interface.parameter(name).is_none() {
+            bail!("The graph has no parameter '{name}'");
+        }
+        for parameter in interface.parameters.iter().filter(|p| &p.name == name) {
+            let Some(Nodes::Parameter(node)) = snarl.get_node_mut(parameter.node) else {
+                unreachable!("Parameters are only collected from parameter nodes");
+            };
+            node.default = node
+                .validate(&values[name])
+                .with_context(|| format!("Invalid value for parameter '{name}'"))?;
+        }
+    }
+    Ok(())
+}
+
+fn plain_json<S: serde::Serializer>(value: &Value, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&value.to_json(), serializer)
+}
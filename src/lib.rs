@@ -0,0 +1,25 @@
+pub mod app;
+pub mod cli;
+pub mod color;
+pub mod curves;
+pub mod document;
+pub mod interface;
+pub mod nodes;
+pub mod nurbs;
+pub mod render;
+pub mod schema;
+pub mod server;
+pub mod shapes;
+pub mod solver;
+pub mod style;
+pub mod tree;
+pub mod value;
+mod viewer;
+
+pub const NUMBER_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 255, 0);
+pub const POINT_COLOR: egui::Color32 = egui::Color32::from_rgb(0, 255, 255);
+pub const COLOR_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 140, 0);
+pub const TRANSFORM_COLOR: egui::Color32 = egui::Color32::from_rgb(0, 200, 100);
+pub const SHAPE_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 0, 255);
+pub const DATA_COLOR: egui::Color32 = egui::Color32::from_rgb(200, 200, 200);
+pub const UNCONNECTED_COLOR: egui::Color32 = egui::Color32::from_rgb(50, 50, 50);
@@ -1,25 +1,20 @@
-use piet::RenderContext;
-
-use crate::shapes::Shapes;
+use crate::{
+    render::{Drawing, Unit},
+    shapes::Shapes,
+    solver::{Access, EvalError, ListMatching, Results},
+    value::{Value, ValueType},
+};
 
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct CanvasNode {
     width: f64,
     height: f64,
     shapes: Vec<Shapes>,
-}
-
-impl CanvasNode {
-    fn draw(&self) -> Vec<u8> {
-        let mut rc = piet_svg::RenderContext::new(piet::kurbo::Size::new(self.width, self.height));
-        rc.clear(None, piet::Color::WHITE);
-        for shape in &self.shapes {
-            rc.stroke(shape.get_shape(), &piet::Color::BLACK, 1.0);
-        }
-        let mut buffer: Vec<u8> = Vec::new();
-        rc.write(&mut buffer).expect("Write worked");
-        buffer
-    }
+    #[serde(default)]
+    matching: ListMatching,
+    /// What the width and height are measured in
+    #[serde(default)]
+    pub unit: Unit,
 }
 
 impl Default for CanvasNode {
@@ -28,6 +23,8 @@ impl Default for CanvasNode {
             width: 400.0,
             height: 300.0,
             shapes: vec![Shapes::Circle(piet::kurbo::Circle::default())],
+            matching: ListMatching::default(),
+            unit: Unit::default(),
         }
     }
 }
@@ -45,10 +42,24 @@ impl super::NodeInfo for CanvasNode {
     fn title() -> String {
         "Canvas".to_string()
     }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            2 => ValueType::Shape,
+            _ => ValueType::Number,
+        }
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Drawing
+    }
 }
 impl super::NodeDowncast for CanvasNode {
     fn try_downcast(from: &super::Nodes) -> Option<&Self> {
-        todo!()
+        match from {
+            super::Nodes::Canvas(node) => Some(node),
+            _ => None,
+        }
     }
 
     fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
@@ -64,41 +75,108 @@ impl super::InputNode<super::Nodes> for CanvasNode {
         ui: &mut egui::Ui,
         scale: f32,
         snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
     ) -> egui_snarl::ui::PinInfo {
         match pin.id.input {
-            0 => super::show_number_input("Width", pin, ui, scale, snarl, |id, snarl| {
+            0 => super::show_number_input("Width", pin, ui, scale, snarl, results, |id, snarl| {
                 &mut super::get_node_mut::<Self>(snarl, id.node).width
             }),
-            1 => super::show_number_input("Height", pin, ui, scale, snarl, |id, snarl| {
+            1 => super::show_number_input("Height", pin, ui, scale, snarl, results, |id, snarl| {
                 &mut super::get_node_mut::<Self>(snarl, id.node).height
             }),
-            // TODO: This input should take the whole list of shapes!
-            2 => super::show_shape_input("Shapes", pin, ui, scale, snarl, |id, snarl| {
-                &mut super::get_node_mut::<Self>(snarl, id.node).shapes[0]
-            }),
+            2 => show_shapes_input(pin, ui, results),
             _ => unreachable!(),
         }
     }
 }
+/// Accepts any number of wires, each carrying shapes, lists or trees of shapes
+fn show_shapes_input(
+    pin: &egui_snarl::InPin,
+    ui: &mut egui::Ui,
+    results: &Results,
+) -> egui_snarl::ui::PinInfo {
+    ui.label("Shapes");
+    if !pin.remotes.is_empty() {
+        match results.input(pin).map(Value::to_shapes) {
+            Some(Ok(shapes)) => ui.label(format!("{} shapes", shapes.len())),
+            Some(Err(_)) | None => ui.label("None"),
+        };
+    }
+    egui_snarl::ui::PinInfo::square().with_fill(crate::SHAPE_COLOR)
+}
+
 impl super::OutputNode<super::Nodes> for CanvasNode {
     fn show_output(
         pin: &egui_snarl::OutPin,
         ui: &mut egui::Ui,
         scale: f32,
-        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
     ) -> egui_snarl::ui::PinInfo {
-        let uri = format!("bytes://canvas{}.svg", pin.id.node.0);
-        let image = egui::Image::from_bytes(
-            uri.to_owned(),
-            super::get_node_mut::<Self>(snarl, pin.id.node).draw(),
-        )
-        .max_width(200.0 * scale)
-        .shrink_to_fit()
-        .show_loading_spinner(true);
+        let Some(Value::Drawing(drawing)) = results.get(pin.id) else {
+            ui.label("Nothing to draw");
+            return egui_snarl::ui::PinInfo::triangle();
+        };
+        // The svg is only rendered again when the drawing got a new revision,
+        // the image of the previous revision is dropped from the cache then
+        let revision = results.revision(pin.id.node).unwrap_or_default();
+        let uri = format!("bytes://canvas{}-{revision}.svg", pin.id.node.0);
+        let cache_id = egui::Id::new(("canvas", pin.id.node));
+        let cached = ui.data(|data| data.get_temp::<String>(cache_id));
+        if cached.as_ref() != Some(&uri) {
+            if let Some(cached) = cached {
+                ui.ctx().forget_image(&cached);
+            }
+            ui.ctx().include_bytes(uri.clone(), drawing.to_svg());
+            ui.data_mut(|data| data.insert_temp(cache_id, uri.clone()));
+        }
+        let image = egui::Image::new(uri)
+            .max_width(200.0 * scale)
+            .shrink_to_fit()
+            .show_loading_spinner(true);
         ui.add(image);
-
-        // TODO: Bad for performance, but necessary to update the image. Would be better to somehow cache the image in the struct itself, so all inputs can refresh it on change
-        ui.ctx().forget_image(&uri);
         egui_snarl::ui::PinInfo::triangle()
     }
 }
+
+impl super::SolveNode for CanvasNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Number(self.width)),
+            1 => Some(Value::Number(self.height)),
+            2 => Some(Value::List(
+                self.shapes.iter().cloned().map(Value::Shape).collect(),
+            )),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let width = inputs[0].as_number()?;
+        let height = inputs[1].as_number()?;
+        if width <= 0.0 || height <= 0.0 {
+            return Err(EvalError::Domain(format!(
+                "Canvas size has to be positive, but is {width}x{height}"
+            )));
+        }
+        let shapes = inputs[2].to_shapes()?;
+        let drawing =
+            Drawing::new(piet::kurbo::Size::new(width, height), shapes).with_unit(self.unit);
+        Ok(vec![Value::Drawing(drawing)])
+    }
+
+    fn input_access(&self, index: usize) -> Access {
+        match index {
+            2 => Access::Tree,
+            _ => Access::Item,
+        }
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
@@ -1,10 +1,16 @@
 use egui_snarl::ui::PinInfo;
 
-use crate::shapes::Shapes;
+use crate::{
+    shapes::Shapes,
+    solver::{EvalError, ListMatching, Results},
+    value::{Value, ValueType},
+};
 
 #[derive(serde::Serialize, serde::Deserialize, Default, Debug)]
 pub struct CircleNode {
     circle: piet::kurbo::Circle,
+    #[serde(default)]
+    matching: ListMatching,
 }
 
 impl CircleNode {
@@ -35,10 +41,24 @@ impl super::NodeInfo for CircleNode {
     fn title() -> String {
         "Circle".to_string()
     }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Point,
+            _ => ValueType::Number,
+        }
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
 }
 impl super::NodeDowncast for CircleNode {
     fn try_downcast(from: &super::Nodes) -> Option<&Self> {
-        todo!()
+        match from {
+            super::Nodes::Circle(node) => Some(node),
+            _ => None,
+        }
     }
 
     fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
@@ -54,12 +74,13 @@ impl super::InputNode<super::Nodes> for CircleNode {
         ui: &mut egui::Ui,
         scale: f32,
         snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
     ) -> egui_snarl::ui::PinInfo {
         match pin.id.input {
-            0 => super::show_point_input("Center", pin, ui, scale, snarl, |id, snarl| {
+            0 => super::show_point_input("Center", pin, ui, scale, snarl, results, |id, snarl| {
                 super::get_node_mut::<Self>(snarl, id.node).center_mut()
             }),
-            1 => super::show_number_input("Radius", pin, ui, scale, snarl, |id, snarl| {
+            1 => super::show_number_input("Radius", pin, ui, scale, snarl, results, |id, snarl| {
                 super::get_node_mut::<Self>(snarl, id.node).radius_mut()
             }),
             _ => unreachable!(),
@@ -69,21 +90,43 @@ impl super::InputNode<super::Nodes> for CircleNode {
 
 impl super::OutputNode<super::Nodes> for CircleNode {
     fn show_output(
-        pin: &egui_snarl::OutPin,
+        _pin: &egui_snarl::OutPin,
         ui: &mut egui::Ui,
-        scale: f32,
-        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
     ) -> egui_snarl::ui::PinInfo {
         ui.label("Circle");
         PinInfo::triangle().with_fill(crate::SHAPE_COLOR)
     }
 }
-impl super::EmitterNode<Shapes> for CircleNode {
-    fn value_out(&self) -> Shapes {
-        Shapes::Circle(self.circle_out().to_owned())
+
+impl super::SolveNode for CircleNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(self.circle.center)),
+            1 => Some(Value::Number(self.circle.radius)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let center = inputs[0].as_point()?;
+        let radius = inputs[1].as_number()?;
+        if radius < 0.0 {
+            return Err(EvalError::Domain(format!(
+                "Radius can't be negative, but is {radius}"
+            )));
+        }
+        let circle = piet::kurbo::Circle::new(center, radius);
+        Ok(vec![Value::Shape(Shapes::Circle(circle))])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
     }
 
-    fn values_out(&self) -> impl Iterator<Item = Shapes> + '_ {
-        (0..1).map(|_| self.value_out())
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
     }
 }
@@ -0,0 +1,948 @@
+use egui_snarl::ui::PinInfo;
+
+use crate::{
+    color::{Color, Interpolation},
+    solver::{Access, EvalError, ListMatching, Results},
+    value::{Value, ValueType},
+};
+
+/// Shows the color or colors on an output pin as swatches
+fn show_color_output(
+    title: &str,
+    pin: &egui_snarl::OutPin,
+    ui: &mut egui::Ui,
+    results: &Results,
+) -> PinInfo {
+    ui.label(title);
+    let colors = results
+        .get(pin.id)
+        .and_then(|value| value.to_colors().ok())
+        .unwrap_or_default();
+    for color in colors.iter().take(8) {
+        egui::color_picker::show_color(ui, color.to_egui(), egui::vec2(12.0, 12.0));
+    }
+    if colors.len() > 8 {
+        ui.label("…");
+    }
+    PinInfo::circle().with_fill(crate::COLOR_COLOR)
+}
+
+/// Checks that a channel lies between 0 and 1
+fn channel(name: &str, value: &Value) -> Result<f64, EvalError> {
+    let channel = value.as_number()?;
+    if (0.0..=1.0).contains(&channel) {
+        Ok(channel)
+    } else {
+        Err(EvalError::Domain(format!(
+            "{name} has to be between 0 and 1, but is {channel}"
+        )))
+    }
+}
+
+/// Builds colors from red, green, blue and alpha between 0 and 1
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug)]
+pub struct RgbColorNode {
+    color: Color,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl super::Node for RgbColorNode {}
+impl super::NodeInfo for RgbColorNode {
+    fn inputs() -> usize {
+        4
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "RGB".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Color
+    }
+}
+impl super::NodeDowncast for RgbColorNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::RgbColor(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::RgbColor(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for RgbColorNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        let title = ["R", "G", "B", "A"][pin.id.input];
+        super::show_number_input(title, pin, ui, scale, snarl, results, |id, snarl| {
+            let color = &mut super::get_node_mut::<Self>(snarl, id.node).color;
+            match id.input {
+                0 => &mut color.r,
+                1 => &mut color.g,
+                2 => &mut color.b,
+                _ => &mut color.a,
+            }
+        })
+    }
+}
+impl super::OutputNode<super::Nodes> for RgbColorNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        show_color_output("Color", pin, ui, results)
+    }
+}
+impl super::SolveNode for RgbColorNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        let color = self.color;
+        [color.r, color.g, color.b, color.a]
+            .get(index)
+            .map(|channel| Value::Number(*channel))
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        Ok(vec![Value::Color(Color::rgba(
+            channel("Red", &inputs[0])?,
+            channel("Green", &inputs[1])?,
+            channel("Blue", &inputs[2])?,
+            channel("Alpha", &inputs[3])?,
+        ))])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Builds colors from hue in degrees, saturation, value and alpha
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct HsvColorNode {
+    hue: f64,
+    saturation: f64,
+    value: f64,
+    alpha: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for HsvColorNode {
+    fn default() -> Self {
+        Self {
+            hue: 0.0,
+            saturation: 1.0,
+            value: 1.0,
+            alpha: 1.0,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for HsvColorNode {}
+impl super::NodeInfo for HsvColorNode {
+    fn inputs() -> usize {
+        4
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "HSV".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Color
+    }
+}
+impl super::NodeDowncast for HsvColorNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::HsvColor(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::HsvColor(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for HsvColorNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        let title = ["Hue", "Saturation", "Value", "Alpha"][pin.id.input];
+        super::show_number_input(title, pin, ui, scale, snarl, results, |id, snarl| {
+            let node = super::get_node_mut::<Self>(snarl, id.node);
+            match id.input {
+                0 => &mut node.hue,
+                1 => &mut node.saturation,
+                2 => &mut node.value,
+                _ => &mut node.alpha,
+            }
+        })
+    }
+}
+impl super::OutputNode<super::Nodes> for HsvColorNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        show_color_output("Color", pin, ui, results)
+    }
+}
+impl super::SolveNode for HsvColorNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        [self.hue, self.saturation, self.value, self.alpha]
+            .get(index)
+            .map(|number| Value::Number(*number))
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let color = Color::from_hsv(
+            inputs[0].as_number()?,
+            channel("Saturation", &inputs[1])?,
+            channel("Value", &inputs[2])?,
+        );
+        Ok(vec![Value::Color(
+            color.with_alpha(channel("Alpha", &inputs[3])?),
+        )])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Builds colors from lightness, chroma and hue in degrees in the
+/// perceptually uniform Oklch space, see [`Color::from_oklch`]
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct OklchColorNode {
+    lightness: f64,
+    chroma: f64,
+    hue: f64,
+    alpha: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for OklchColorNode {
+    fn default() -> Self {
+        Self {
+            lightness: 0.7,
+            chroma: 0.15,
+            hue: 30.0,
+            alpha: 1.0,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for OklchColorNode {}
+impl super::NodeInfo for OklchColorNode {
+    fn inputs() -> usize {
+        4
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "OKLCH".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Color
+    }
+}
+impl super::NodeDowncast for OklchColorNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::OklchColor(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::OklchColor(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for OklchColorNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        let title = ["Lightness", "Chroma", "Hue", "Alpha"][pin.id.input];
+        super::show_number_input(title, pin, ui, scale, snarl, results, |id, snarl| {
+            let node = super::get_node_mut::<Self>(snarl, id.node);
+            match id.input {
+                0 => &mut node.lightness,
+                1 => &mut node.chroma,
+                2 => &mut node.hue,
+                _ => &mut node.alpha,
+            }
+        })
+    }
+}
+impl super::OutputNode<super::Nodes> for OklchColorNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        show_color_output("Color", pin, ui, results)
+    }
+}
+impl super::SolveNode for OklchColorNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        [self.lightness, self.chroma, self.hue, self.alpha]
+            .get(index)
+            .map(|number| Value::Number(*number))
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let chroma = inputs[1].as_number()?;
+        if chroma < 0.0 {
+            return Err(EvalError::Domain(format!(
+                "Chroma can't be negative, but is {chroma}"
+            )));
+        }
+        let color = Color::from_oklch(
+            channel("Lightness", &inputs[0])?,
+            chroma,
+            inputs[2].as_number()?,
+        );
+        Ok(vec![Value::Color(
+            color.with_alpha(channel("Alpha", &inputs[3])?),
+        )])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Reads colors from hex strings like `#ff8800`
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct HexColorNode {
+    hex: String,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for HexColorNode {
+    fn default() -> Self {
+        Self {
+            hex: "#ff8800".to_string(),
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for HexColorNode {}
+impl super::NodeInfo for HexColorNode {
+    fn inputs() -> usize {
+        1
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Hex Color".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Text
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Color
+    }
+}
+impl super::NodeDowncast for HexColorNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::HexColor(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::HexColor(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for HexColorNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        super::show_text_input("Hex", pin, ui, scale, snarl, results, |id, snarl| {
+            &mut super::get_node_mut::<Self>(snarl, id.node).hex
+        })
+    }
+}
+impl super::OutputNode<super::Nodes> for HexColorNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        show_color_output("Color", pin, ui, results)
+    }
+}
+impl super::SolveNode for HexColorNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        Some(Value::Text(self.hex.clone()))
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let hex = inputs[0].as_text()?;
+        let color = Color::from_hex(&hex)
+            .ok_or_else(|| EvalError::Domain(format!("'{hex}' is not a hex color like #ff8800")))?;
+        Ok(vec![Value::Color(color)])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Samples evenly spaced colors between a list of color stops
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct GradientNode {
+    stops: Vec<Color>,
+    count: f64,
+    #[serde(default)]
+    pub space: Interpolation,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for GradientNode {
+    fn default() -> Self {
+        Self {
+            stops: vec![Color::rgb(0.1, 0.2, 0.6), Color::rgb(1.0, 0.8, 0.2)],
+            count: 5.0,
+            space: Interpolation::default(),
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl GradientNode {
+    /// Edits the stops of the node itself, shown while no stops are wired in
+    fn show_stops(&mut self, ui: &mut egui::Ui) {
+        let mut removed = None;
+        for (index, stop) in self.stops.iter_mut().enumerate() {
+            let mut rgba = [stop.r, stop.g, stop.b, stop.a].map(|c| c as f32);
+            let response = ui.color_edit_button_rgba_unmultiplied(&mut rgba);
+            if response.changed() {
+                let [r, g, b, a] = rgba.map(f64::from);
+                *stop = Color::rgba(r, g, b, a);
+            }
+            response.context_menu(|ui| {
+                if ui.button("Remove").clicked() {
+                    removed = Some(index);
+                    ui.close_menu();
+                }
+            });
+        }
+        if let Some(index) = removed.filter(|_| self.stops.len() > 1) {
+            self.stops.remove(index);
+        }
+        if ui.small_button("+").clicked() {
+            let last = self.stops.last().copied().unwrap_or_default();
+            self.stops.push(last);
+        }
+    }
+}
+
+/// The colors at `count` evenly spaced positions along the stops
+pub fn gradient(stops: &[Color], count: usize, space: Interpolation) -> Vec<Color> {
+    match (stops, count) {
+        ([], _) | (_, 0) => Vec::new(),
+        ([single], _) => vec![*single; count],
+        (_, 1) => vec![stops[0]],
+        _ => (0..count)
+            .map(|index| {
+                let position = index as f64 / (count - 1) as f64 * (stops.len() - 1) as f64;
+                let segment = (position.floor() as usize).min(stops.len() - 2);
+                stops[segment].mix(stops[segment + 1], position - segment as f64, space)
+            })
+            .collect(),
+    }
+}
+
+impl super::Node for GradientNode {}
+impl super::NodeInfo for GradientNode {
+    fn inputs() -> usize {
+        2
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Gradient".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Color,
+            _ => ValueType::Number,
+        }
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Color
+    }
+}
+impl super::NodeDowncast for GradientNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Gradient(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Gradient(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for GradientNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => {
+                ui.label("Stops");
+                if pin.remotes.is_empty() {
+                    super::get_node_mut::<Self>(snarl, pin.id.node).show_stops(ui);
+                } else {
+                    super::show_remote_value(ui, results.input(pin));
+                }
+                PinInfo::square().with_fill(crate::COLOR_COLOR)
+            }
+            1 => super::show_number_input("Count", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).count
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for GradientNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        show_color_output("Colors", pin, ui, results)
+    }
+}
+impl super::SolveNode for GradientNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::List(
+                self.stops.iter().copied().map(Value::Color).collect(),
+            )),
+            1 => Some(Value::Number(self.count)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let stops = inputs[0].to_colors()?;
+        if stops.is_empty() {
+            return Err(EvalError::Domain(
+                "A gradient needs at least one color stop".to_string(),
+            ));
+        }
+        let count = inputs[1].as_count()?;
+        let colors = gradient(&stops, count, self.space);
+        Ok(vec![Value::List(
+            colors.into_iter().map(Value::Color).collect(),
+        )])
+    }
+
+    fn input_access(&self, index: usize) -> Access {
+        match index {
+            0 => Access::List,
+            _ => Access::Item,
+        }
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Well known color schemes
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    /// Perceptually uniform from dark blue to yellow
+    #[default]
+    Viridis,
+    /// Perceptually uniform from black over purple to light yellow
+    Magma,
+    /// Strong colors telling categories apart
+    Set1,
+    Pastel,
+    Grayscale,
+}
+
+impl Palette {
+    pub const ALL: [Self; 5] = [
+        Self::Viridis,
+        Self::Magma,
+        Self::Set1,
+        Self::Pastel,
+        Self::Grayscale,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Viridis => "Viridis",
+            Self::Magma => "Magma",
+            Self::Set1 => "Set 1",
+            Self::Pastel => "Pastel",
+            Self::Grayscale => "Grayscale",
+        }
+    }
+
+    pub fn colors(self) -> Vec<Color> {
+        let hex: &[&str] = match self {
+            Self::Viridis => &["440154", "414487", "2a788e", "22a884", "7ad151", "fde725"],
+            Self::Magma => &["000004", "3b0f70", "8c2981", "de4968", "fe9f6d", "fcfdbf"],
+            Self::Set1 => &[
+                "e41a1c", "377eb8", "4daf4a", "984ea3", "ff7f00", "ffff33", "a65628", "f781bf",
+            ],
+            Self::Pastel => &[
+                "fbb4ae", "b3cde3", "ccebc5", "decbe4", "fed9a6", "ffffcc", "e5d8bd", "fddaec",
+            ],
+            Self::Grayscale => &["000000", "404040", "808080", "bfbfbf", "ffffff"],
+        };
+        hex.iter()
+            .map(|hex| Color::from_hex(hex).expect("Palettes are valid hex colors"))
+            .collect()
+    }
+}
+
+/// Outputs the colors of a well known palette
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug)]
+pub struct PaletteNode {
+    pub palette: Palette,
+}
+
+impl super::Node for PaletteNode {}
+impl super::NodeInfo for PaletteNode {
+    fn inputs() -> usize {
+        0
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Palette".to_string()
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Color
+    }
+}
+impl super::NodeDowncast for PaletteNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Palette(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Palette(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for PaletteNode {
+    fn show_input(
+        _pin: &egui_snarl::InPin,
+        _ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        unreachable!("Palettes have no inputs")
+    }
+}
+impl super::OutputNode<super::Nodes> for PaletteNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        show_color_output("Colors", pin, ui, results)
+    }
+}
+impl super::SolveNode for PaletteNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        None
+    }
+
+    fn solve(&self, _inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        Ok(vec![Value::List(
+            self.palette
+                .colors()
+                .into_iter()
+                .map(Value::Color)
+                .collect(),
+        )])
+    }
+}
+
+/// Color schemes built by rotating the hue of a base color
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Harmony {
+    #[default]
+    Complementary,
+    Analogous,
+    Triadic,
+    Tetradic,
+}
+
+impl Harmony {
+    pub const ALL: [Self; 4] = [
+        Self::Complementary,
+        Self::Analogous,
+        Self::Triadic,
+        Self::Tetradic,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Complementary => "Complementary",
+            Self::Analogous => "Analogous",
+            Self::Triadic => "Triadic",
+            Self::Tetradic => "Tetradic",
+        }
+    }
+
+    /// Hue rotations in degrees, the base color being the first
+    fn rotations(self) -> &'static [f64] {
+        match self {
+            Self::Complementary => &[0.0, 180.0],
+            Self::Analogous => &[0.0, -30.0, 30.0],
+            Self::Triadic => &[0.0, 120.0, 240.0],
+            Self::Tetradic => &[0.0, 90.0, 180.0, 270.0],
+        }
+    }
+}
+
+/// Builds a palette around a base color by rotating its hue in Oklch,
+/// which keeps the lightness of all colors alike
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct HarmonyNode {
+    base: Color,
+    #[serde(default)]
+    pub harmony: Harmony,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for HarmonyNode {
+    fn default() -> Self {
+        Self {
+            base: Color::rgb(0.9, 0.4, 0.1),
+            harmony: Harmony::default(),
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for HarmonyNode {}
+impl super::NodeInfo for HarmonyNode {
+    fn inputs() -> usize {
+        1
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Harmony".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Color
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Color
+    }
+}
+impl super::NodeDowncast for HarmonyNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Harmony(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Harmony(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for HarmonyNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        super::show_color_input("Base", pin, ui, scale, snarl, results, |id, snarl| {
+            &mut super::get_node_mut::<Self>(snarl, id.node).base
+        })
+    }
+}
+impl super::OutputNode<super::Nodes> for HarmonyNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        show_color_output("Colors", pin, ui, results)
+    }
+}
+impl super::SolveNode for HarmonyNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        Some(Value::Color(self.base))
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let base = inputs[0].as_color()?;
+        let (lightness, chroma, hue) = base.to_oklch();
+        let colors = self
+            .harmony
+            .rotations()
+            .iter()
+            .enumerate()
+            .map(|(index, rotation)| match index {
+                // The base color stays exactly as it is
+                0 => Value::Color(base),
+                _ => Value::Color(
+                    Color::from_oklch(lightness, chroma, hue + rotation).with_alpha(base.a),
+                ),
+            })
+            .collect();
+        Ok(vec![Value::List(colors)])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
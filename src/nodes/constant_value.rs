@@ -1,7 +1,12 @@
 use egui::Ui;
-use egui_snarl::{ui::PinInfo, OutPin};
+use egui_snarl::ui::PinInfo;
 
-use super::{Node, NodeInfo};
+use crate::{
+    solver::EvalError,
+    value::{Value, ValueType},
+};
+
+use super::{Node, NodeInfo, SolveNode};
 
 #[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
 pub enum ConstantValue {
@@ -55,6 +60,10 @@ impl NodeInfo for ConstantValueNode {
     fn title() -> String {
         "Constant".to_string()
     }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
 }
 
 impl ConstantValueNode {
@@ -75,12 +84,19 @@ impl ConstantValueNode {
                 ui.selectable_value(&mut self.value, ConstantValue::Rho, "Rho");
                 ui.selectable_value(&mut self.value, ConstantValue::Custom, "Custom");
             });
-        match self.value {
-            ConstantValue::Custom => {
-                ui.add(egui::DragValue::new(&mut self.value_overwrite));
-            }
-            _ => (),
+        if self.value == ConstantValue::Custom {
+            ui.add(egui::DragValue::new(&mut self.value_overwrite));
         }
         PinInfo::square().with_fill(crate::NUMBER_COLOR)
     }
 }
+
+impl SolveNode for ConstantValueNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        None
+    }
+
+    fn solve(&self, _inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        Ok(vec![Value::Number(self.number_out())])
+    }
+}
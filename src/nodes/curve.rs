@@ -0,0 +1,496 @@
+use egui_snarl::ui::PinInfo;
+
+use crate::{
+    curves::{self, BezierDegree},
+    nurbs::Nurbs,
+    shapes::Shapes,
+    solver::{Access, EvalError, ListMatching, Results},
+    value::{Value, ValueType},
+};
+
+fn show_path_output(ui: &mut egui::Ui) -> PinInfo {
+    ui.label("Path");
+    PinInfo::triangle().with_fill(crate::SHAPE_COLOR)
+}
+
+/// Bezier curve along a list of control points, where every segment
+/// ends on the point the next one starts from
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug)]
+pub struct BezierNode {
+    #[serde(default)]
+    pub degree: BezierDegree,
+}
+
+impl super::Node for BezierNode {}
+impl super::NodeInfo for BezierNode {
+    fn inputs() -> usize {
+        1
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Bezier".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Point
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
+}
+impl super::NodeDowncast for BezierNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Bezier(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Bezier(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for BezierNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        super::show_data_input("Control Points", pin, ui, scale, results)
+            .with_fill(crate::POINT_COLOR)
+    }
+}
+impl super::OutputNode<super::Nodes> for BezierNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        let node = super::get_node_mut::<Self>(snarl, pin.id.node);
+        for degree in BezierDegree::ALL {
+            ui.radio_value(&mut node.degree, degree, degree.name());
+        }
+        show_path_output(ui)
+    }
+}
+impl super::SolveNode for BezierNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        None
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let points = inputs[0].to_points()?;
+        let path = curves::bezier(&points, self.degree)?;
+        Ok(vec![Value::Shape(Shapes::Path(path))])
+    }
+
+    fn input_access(&self, _index: usize) -> Access {
+        Access::List
+    }
+}
+
+/// Catmull-Rom spline through a list of points, where the alpha
+/// picks between the uniform, centripetal and chordal variants
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct CatmullRomNode {
+    alpha: f64,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for CatmullRomNode {
+    fn default() -> Self {
+        Self {
+            alpha: 0.5,
+            closed: false,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for CatmullRomNode {}
+impl super::NodeInfo for CatmullRomNode {
+    fn inputs() -> usize {
+        2
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Catmull-Rom".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Point,
+            _ => ValueType::Number,
+        }
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
+}
+impl super::NodeDowncast for CatmullRomNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::CatmullRom(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::CatmullRom(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for CatmullRomNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_data_input("Points", pin, ui, scale, results)
+                .with_fill(crate::POINT_COLOR),
+            1 => super::show_number_input("Alpha", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).alpha
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for CatmullRomNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        let node = super::get_node_mut::<Self>(snarl, pin.id.node);
+        ui.checkbox(&mut node.closed, "Closed");
+        show_path_output(ui)
+    }
+}
+impl super::SolveNode for CatmullRomNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            1 => Some(Value::Number(self.alpha)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let points = inputs[0].to_points()?;
+        let alpha = inputs[1].as_number()?;
+        if !(0.0..=1.0).contains(&alpha) {
+            return Err(EvalError::Domain(format!(
+                "Alpha has to be between 0 and 1, but is {alpha}"
+            )));
+        }
+        let path = curves::catmull_rom(&points, alpha, self.closed)?;
+        Ok(vec![Value::Shape(Shapes::Path(path))])
+    }
+
+    fn input_access(&self, index: usize) -> Access {
+        match index {
+            0 => Access::List,
+            _ => Access::Item,
+        }
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Cubic spline through a list of points, bending as evenly as possible
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug)]
+pub struct SplineNode {
+    #[serde(default)]
+    pub closed: bool,
+}
+
+impl super::Node for SplineNode {}
+impl super::NodeInfo for SplineNode {
+    fn inputs() -> usize {
+        1
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Spline".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Point
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
+}
+impl super::NodeDowncast for SplineNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Spline(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Spline(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for SplineNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        super::show_data_input("Points", pin, ui, scale, results).with_fill(crate::POINT_COLOR)
+    }
+}
+impl super::OutputNode<super::Nodes> for SplineNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        let node = super::get_node_mut::<Self>(snarl, pin.id.node);
+        ui.checkbox(&mut node.closed, "Closed");
+        show_path_output(ui)
+    }
+}
+impl super::SolveNode for SplineNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        None
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let points = inputs[0].to_points()?;
+        let path = curves::interpolating(&points, self.closed)?;
+        Ok(vec![Value::Shape(Shapes::Path(path))])
+    }
+
+    fn input_access(&self, _index: usize) -> Access {
+        Access::List
+    }
+}
+
+/// NURBS curve from control points with optional weights and knots,
+/// evaluated at fractions of its length in parameter space
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct NurbsNode {
+    degree: f64,
+    parameter: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for NurbsNode {
+    fn default() -> Self {
+        Self {
+            degree: 3.0,
+            parameter: 0.5,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for NurbsNode {}
+impl super::NodeInfo for NurbsNode {
+    fn inputs() -> usize {
+        5
+    }
+
+    fn outputs() -> usize {
+        3
+    }
+
+    fn title() -> String {
+        "NURBS".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Point,
+            _ => ValueType::Number,
+        }
+    }
+
+    fn output_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Curve,
+            _ => ValueType::Point,
+        }
+    }
+}
+impl super::NodeDowncast for NurbsNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Nurbs(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Nurbs(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for NurbsNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_data_input("Control Points", pin, ui, scale, results)
+                .with_fill(crate::POINT_COLOR),
+            1 => super::show_data_input("Weights", pin, ui, scale, results)
+                .with_fill(crate::NUMBER_COLOR),
+            2 => super::show_number_input("Degree", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).degree
+            }),
+            3 => super::show_data_input("Knots", pin, ui, scale, results)
+                .with_fill(crate::NUMBER_COLOR),
+            4 => super::show_number_input(
+                "Parameters",
+                pin,
+                ui,
+                scale,
+                snarl,
+                results,
+                |id, snarl| &mut super::get_node_mut::<Self>(snarl, id.node).parameter,
+            ),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for NurbsNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        match pin.id.output {
+            0 => {
+                ui.label("Curve");
+                PinInfo::triangle().with_fill(crate::SHAPE_COLOR)
+            }
+            1 => {
+                ui.label("Points");
+                PinInfo::circle().with_fill(crate::POINT_COLOR)
+            }
+            2 => {
+                ui.label("Tangents");
+                PinInfo::circle().with_fill(crate::POINT_COLOR)
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::SolveNode for NurbsNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            // No weights and no knots make a clamped uniform B-spline
+            1 | 3 => Some(Value::List(Vec::new())),
+            2 => Some(Value::Number(self.degree)),
+            4 => Some(Value::Number(self.parameter)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let points = inputs[0].to_points()?;
+        let weights = Some(inputs[1].to_numbers()?).filter(|weights| !weights.is_empty());
+        let degree = inputs[2].as_count()?;
+        let knots = Some(inputs[3].to_numbers()?).filter(|knots| !knots.is_empty());
+        let curve = Nurbs::new(points, weights, degree, knots)?;
+
+        let parameters = inputs[4]
+            .to_numbers()?
+            .into_iter()
+            .map(|fraction| curve.parameter_at(fraction))
+            .collect::<Vec<_>>();
+        let points = parameters
+            .iter()
+            .map(|parameter| Value::Point(curve.point_at(*parameter)))
+            .collect();
+        let tangents = parameters
+            .iter()
+            .map(|parameter| {
+                let derivative = curve.derivative_at(*parameter);
+                let length = derivative.hypot();
+                let tangent = if length > 0.0 {
+                    derivative / length
+                } else {
+                    derivative
+                };
+                Value::Point(tangent.to_point())
+            })
+            .collect();
+        Ok(vec![
+            Value::Curve(curve),
+            Value::List(points),
+            Value::List(tangents),
+        ])
+    }
+
+    fn input_access(&self, index: usize) -> Access {
+        match index {
+            2 => Access::Item,
+            _ => Access::List,
+        }
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
@@ -0,0 +1,847 @@
+use egui_snarl::ui::PinInfo;
+
+use crate::{
+    solver::{EvalError, ListMatching, Results},
+    value::{Value, ValueType},
+};
+
+fn show_result_output(ui: &mut egui::Ui) -> PinInfo {
+    ui.label("Result");
+    PinInfo::square().with_fill(crate::NUMBER_COLOR)
+}
+
+/// Reads all inputs as numbers
+fn numbers<const N: usize>(inputs: &[Value]) -> Result<[f64; N], EvalError> {
+    let mut numbers = [0.0; N];
+    for (number, input) in numbers.iter_mut().zip(inputs) {
+        *number = input.as_number()?;
+    }
+    Ok(numbers)
+}
+
+/// Results which aren't finite numbers are errors, so they
+/// don't end up as invisible shapes further down the graph
+fn finite(name: &str, result: f64) -> Result<f64, EvalError> {
+    if result.is_finite() {
+        Ok(result)
+    } else {
+        Err(EvalError::Domain(format!(
+            "{name} has no finite result for these numbers"
+        )))
+    }
+}
+
+/// Operations on two numbers, with angles in radians
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    #[default]
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+    /// The remainder of a division, which has the sign of the
+    /// divisor so it repeats evenly across zero
+    Modulo,
+    Min,
+    Max,
+    /// The angle of the point at x and y, from -pi to pi
+    Atan2,
+}
+
+impl Operation {
+    pub const ALL: [Self; 9] = [
+        Self::Add,
+        Self::Subtract,
+        Self::Multiply,
+        Self::Divide,
+        Self::Power,
+        Self::Modulo,
+        Self::Min,
+        Self::Max,
+        Self::Atan2,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Add => "Add",
+            Self::Subtract => "Subtract",
+            Self::Multiply => "Multiply",
+            Self::Divide => "Divide",
+            Self::Power => "Power",
+            Self::Modulo => "Modulo",
+            Self::Min => "Min",
+            Self::Max => "Max",
+            Self::Atan2 => "Atan2",
+        }
+    }
+
+    fn input_names(self) -> [&'static str; 2] {
+        match self {
+            Self::Power => ["Base", "Exponent"],
+            Self::Atan2 => ["Y", "X"],
+            _ => ["A", "B"],
+        }
+    }
+
+    pub fn apply(self, a: f64, b: f64) -> Result<f64, EvalError> {
+        let result = match self {
+            Self::Add => a + b,
+            Self::Subtract => a - b,
+            Self::Multiply => a * b,
+            Self::Divide | Self::Modulo if b == 0.0 => {
+                return Err(EvalError::Domain("Can't divide by zero".to_string()))
+            }
+            Self::Divide => a / b,
+            Self::Power => a.powf(b),
+            Self::Modulo => a - b * (a / b).floor(),
+            Self::Min => a.min(b),
+            Self::Max => a.max(b),
+            Self::Atan2 => a.atan2(b),
+        };
+        finite(self.name(), result)
+    }
+}
+
+/// Functions of a single number, with angles in radians
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    #[default]
+    Sin,
+    Cos,
+    Tan,
+    Floor,
+    Ceil,
+    /// Rounds halfway cases away from zero
+    Round,
+    Abs,
+}
+
+impl Function {
+    pub const ALL: [Self; 7] = [
+        Self::Sin,
+        Self::Cos,
+        Self::Tan,
+        Self::Floor,
+        Self::Ceil,
+        Self::Round,
+        Self::Abs,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sin => "Sin",
+            Self::Cos => "Cos",
+            Self::Tan => "Tan",
+            Self::Floor => "Floor",
+            Self::Ceil => "Ceil",
+            Self::Round => "Round",
+            Self::Abs => "Abs",
+        }
+    }
+
+    pub fn apply(self, x: f64) -> Result<f64, EvalError> {
+        let result = match self {
+            Self::Sin => x.sin(),
+            Self::Cos => x.cos(),
+            Self::Tan => x.tan(),
+            Self::Floor => x.floor(),
+            Self::Ceil => x.ceil(),
+            Self::Round => x.round(),
+            Self::Abs => x.abs(),
+        };
+        finite(self.name(), result)
+    }
+}
+
+/// Applies an [`Operation`] to two numbers, it is titled after the
+/// operation which can be switched in its context menu
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct MathNode {
+    pub operation: Operation,
+    a: f64,
+    b: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl MathNode {
+    pub fn new(operation: Operation) -> Self {
+        Self {
+            operation,
+            a: 0.0,
+            b: 1.0,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl Default for MathNode {
+    fn default() -> Self {
+        Self::new(Operation::default())
+    }
+}
+
+impl super::Node for MathNode {}
+impl super::NodeInfo for MathNode {
+    fn inputs() -> usize {
+        2
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Math".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+}
+impl super::NodeDowncast for MathNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Math(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Math(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for MathNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        let names = super::get_node_mut::<Self>(snarl, pin.id.node)
+            .operation
+            .input_names();
+        match pin.id.input {
+            0 => super::show_number_input(names[0], pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).a
+            }),
+            1 => super::show_number_input(names[1], pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).b
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for MathNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_result_output(ui)
+    }
+}
+impl super::SolveNode for MathNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Number(self.a)),
+            1 => Some(Value::Number(self.b)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let [a, b] = numbers(inputs)?;
+        Ok(vec![Value::Number(self.operation.apply(a, b)?)])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Applies a [`Function`] to a number, it is titled after the
+/// function which can be switched in its context menu
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct FunctionNode {
+    pub function: Function,
+    x: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl FunctionNode {
+    pub fn new(function: Function) -> Self {
+        Self {
+            function,
+            x: 0.0,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl Default for FunctionNode {
+    fn default() -> Self {
+        Self::new(Function::default())
+    }
+}
+
+impl super::Node for FunctionNode {}
+impl super::NodeInfo for FunctionNode {
+    fn inputs() -> usize {
+        1
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Function".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+}
+impl super::NodeDowncast for FunctionNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Function(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Function(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for FunctionNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        super::show_number_input("X", pin, ui, scale, snarl, results, |id, snarl| {
+            &mut super::get_node_mut::<Self>(snarl, id.node).x
+        })
+    }
+}
+impl super::OutputNode<super::Nodes> for FunctionNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_result_output(ui)
+    }
+}
+impl super::SolveNode for FunctionNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Number(self.x)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let [x] = numbers(inputs)?;
+        Ok(vec![Value::Number(self.function.apply(x)?)])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Limits a number to a range, which must not be reversed
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ClampNode {
+    value: f64,
+    min: f64,
+    max: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for ClampNode {
+    fn default() -> Self {
+        Self {
+            value: 0.0,
+            min: 0.0,
+            max: 1.0,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for ClampNode {}
+impl super::NodeInfo for ClampNode {
+    fn inputs() -> usize {
+        3
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Clamp".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+}
+impl super::NodeDowncast for ClampNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Clamp(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Clamp(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for ClampNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_number_input("Value", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).value
+            }),
+            1 => super::show_number_input("Min", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).min
+            }),
+            2 => super::show_number_input("Max", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).max
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for ClampNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_result_output(ui)
+    }
+}
+impl super::SolveNode for ClampNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Number(self.value)),
+            1 => Some(Value::Number(self.min)),
+            2 => Some(Value::Number(self.max)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        Ok(vec![Value::Number(self.apply(inputs)?)])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+impl ClampNode {
+    fn apply(&self, inputs: &[Value]) -> Result<f64, EvalError> {
+        let [value, min, max] = numbers(inputs)?;
+        if [value, min, max].iter().any(|number| number.is_nan()) {
+            return Err(EvalError::Domain(
+                "Clamp needs numbers, but got NaN".to_string(),
+            ));
+        }
+        if min > max {
+            return Err(EvalError::Domain(format!(
+                "Min can't be larger than max, but {min} is larger than {max}"
+            )));
+        }
+        Ok(value.max(min).min(max))
+    }
+}
+
+/// Blends linearly from one number to another, where a factor of 0 gives
+/// the first and 1 the second number. Other factors extrapolate
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct LerpNode {
+    a: f64,
+    b: f64,
+    factor: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for LerpNode {
+    fn default() -> Self {
+        Self {
+            a: 0.0,
+            b: 1.0,
+            factor: 0.5,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for LerpNode {}
+impl super::NodeInfo for LerpNode {
+    fn inputs() -> usize {
+        3
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Lerp".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+}
+impl super::NodeDowncast for LerpNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Lerp(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Lerp(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for LerpNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_number_input("A", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).a
+            }),
+            1 => super::show_number_input("B", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).b
+            }),
+            2 => super::show_number_input("Factor", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).factor
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for LerpNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_result_output(ui)
+    }
+}
+impl super::SolveNode for LerpNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Number(self.a)),
+            1 => Some(Value::Number(self.b)),
+            2 => Some(Value::Number(self.factor)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        Ok(vec![Value::Number(self.apply(inputs)?)])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+impl LerpNode {
+    fn apply(&self, inputs: &[Value]) -> Result<f64, EvalError> {
+        let [a, b, factor] = numbers(inputs)?;
+        finite("Lerp", a + (b - a) * factor)
+    }
+}
+
+/// Maps a number from one range onto another, keeping its relative
+/// position. Numbers outside of the first range end up outside of the second
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RemapNode {
+    value: f64,
+    from_start: f64,
+    from_end: f64,
+    to_start: f64,
+    to_end: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for RemapNode {
+    fn default() -> Self {
+        Self {
+            value: 0.5,
+            from_start: 0.0,
+            from_end: 1.0,
+            to_start: 0.0,
+            to_end: 100.0,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for RemapNode {}
+impl super::NodeInfo for RemapNode {
+    fn inputs() -> usize {
+        5
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Remap".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+}
+impl super::NodeDowncast for RemapNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Remap(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Remap(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for RemapNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_number_input("Value", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).value
+            }),
+            1 => super::show_number_input(
+                "From Start",
+                pin,
+                ui,
+                scale,
+                snarl,
+                results,
+                |id, snarl| &mut super::get_node_mut::<Self>(snarl, id.node).from_start,
+            ),
+            2 => {
+                super::show_number_input("From End", pin, ui, scale, snarl, results, |id, snarl| {
+                    &mut super::get_node_mut::<Self>(snarl, id.node).from_end
+                })
+            }
+            3 => {
+                super::show_number_input("To Start", pin, ui, scale, snarl, results, |id, snarl| {
+                    &mut super::get_node_mut::<Self>(snarl, id.node).to_start
+                })
+            }
+            4 => super::show_number_input("To End", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).to_end
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for RemapNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_result_output(ui)
+    }
+}
+impl super::SolveNode for RemapNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Number(self.value)),
+            1 => Some(Value::Number(self.from_start)),
+            2 => Some(Value::Number(self.from_end)),
+            3 => Some(Value::Number(self.to_start)),
+            4 => Some(Value::Number(self.to_end)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        Ok(vec![Value::Number(self.apply(inputs)?)])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+impl RemapNode {
+    fn apply(&self, inputs: &[Value]) -> Result<f64, EvalError> {
+        let [value, from_start, from_end, to_start, to_end] = numbers(inputs)?;
+        if from_start == from_end {
+            return Err(EvalError::Domain(
+                "The range to map from can't be empty".to_string(),
+            ));
+        }
+        let factor = (value - from_start) / (from_end - from_start);
+        finite("Remap", to_start + (to_end - to_start) * factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(numbers: &[f64]) -> Vec<Value> {
+        numbers.iter().copied().map(Value::Number).collect()
+    }
+
+    #[test]
+    fn operations_and_functions() {
+        assert_eq!(Operation::Subtract.apply(1.0, 3.0), Ok(-2.0));
+        assert_eq!(Operation::Power.apply(2.0, 10.0), Ok(1024.0));
+        // The remainder takes the sign of the divisor
+        assert_eq!(Operation::Modulo.apply(-1.0, 3.0), Ok(2.0));
+        assert_eq!(Operation::Modulo.apply(1.0, -3.0), Ok(-2.0));
+        assert!(Operation::Divide.apply(1.0, 0.0).is_err());
+        assert!(Operation::Modulo.apply(1.0, 0.0).is_err());
+        assert!(Operation::Multiply.apply(f64::MAX, 2.0).is_err());
+        assert!(Operation::Power.apply(-1.0, 0.5).is_err());
+
+        assert_eq!(Function::Round.apply(-2.5), Ok(-3.0));
+        assert_eq!(Function::Abs.apply(-2.0), Ok(2.0));
+        assert!(Function::Tan.apply(f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn clamp() {
+        let clamp = ClampNode::default();
+        assert_eq!(clamp.apply(&numbers(&[5.0, 0.0, 1.0])), Ok(1.0));
+        assert_eq!(clamp.apply(&numbers(&[-5.0, 0.0, 1.0])), Ok(0.0));
+        assert_eq!(clamp.apply(&numbers(&[0.5, 0.0, 1.0])), Ok(0.5));
+        assert!(clamp.apply(&numbers(&[0.5, 1.0, 0.0])).is_err());
+        // NaN would otherwise slip through max and min unclamped
+        assert!(clamp.apply(&numbers(&[f64::NAN, 0.0, 1.0])).is_err());
+        assert!(clamp.apply(&numbers(&[0.5, f64::NAN, 1.0])).is_err());
+    }
+
+    #[test]
+    fn lerp() {
+        let lerp = LerpNode::default();
+        assert_eq!(lerp.apply(&numbers(&[2.0, 4.0, 0.0])), Ok(2.0));
+        assert_eq!(lerp.apply(&numbers(&[2.0, 4.0, 0.5])), Ok(3.0));
+        assert_eq!(lerp.apply(&numbers(&[2.0, 4.0, 2.0])), Ok(6.0));
+        assert!(lerp.apply(&numbers(&[-f64::MAX, f64::MAX, 1.0])).is_err());
+    }
+
+    #[test]
+    fn remap() {
+        let remap = RemapNode::default();
+        assert_eq!(remap.apply(&numbers(&[5.0, 0.0, 10.0, 0.0, 1.0])), Ok(0.5));
+        // Ranges may run backwards, and numbers may lie outside of them
+        assert_eq!(remap.apply(&numbers(&[2.0, 0.0, 1.0, 1.0, 0.0])), Ok(-1.0));
+        assert!(remap.apply(&numbers(&[5.0, 1.0, 1.0, 0.0, 1.0])).is_err());
+        assert!(remap
+            .apply(&numbers(&[1.0, 0.0, 1e-300, 0.0, 1e300]))
+            .is_err());
+    }
+}
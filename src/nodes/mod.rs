@@ -1,32 +1,348 @@
 use egui::Ui;
 use egui_snarl::{ui::PinInfo, InPin, OutPin, Snarl};
 
-use crate::shapes::Shapes;
-
-use self::{
-    canvas::CanvasNode, circle::CircleNode, constant_value::ConstantValueNode, point::PointNode,
-    range::RangeNode, repeat::RepeatShapeNode, sink::SinkNode,
+use crate::{
+    solver::{Access, EvalError, ListMatching, Results},
+    value::{Value, ValueType},
 };
 
 pub mod canvas;
 pub mod circle;
+pub mod color;
 pub mod constant_value;
+pub mod curve;
+pub mod math;
+pub mod parameter;
 pub mod point;
+pub mod primitive;
 pub mod range;
 pub mod repeat;
 pub mod sink;
+pub mod style;
+pub mod transform;
+pub mod tree;
+
+/// Expands to a match over all node types, so a new node type
+/// only has to be registered here and in the [`Nodes`] enum.
+///
+/// `dispatch!(nodes, node => ..)` binds the node stored inside,
+/// `dispatch!(nodes, type N => ..)` binds its concrete type instead
+macro_rules! dispatch {
+    ($nodes:expr, type $ty:ident => $body:expr) => {
+        match $nodes {
+            $crate::nodes::Nodes::ConstantValueNode(_) => {
+                type $ty = $crate::nodes::constant_value::ConstantValueNode;
+                $body
+            }
+            $crate::nodes::Nodes::Math(_) => {
+                type $ty = $crate::nodes::math::MathNode;
+                $body
+            }
+            $crate::nodes::Nodes::Function(_) => {
+                type $ty = $crate::nodes::math::FunctionNode;
+                $body
+            }
+            $crate::nodes::Nodes::Clamp(_) => {
+                type $ty = $crate::nodes::math::ClampNode;
+                $body
+            }
+            $crate::nodes::Nodes::Lerp(_) => {
+                type $ty = $crate::nodes::math::LerpNode;
+                $body
+            }
+            $crate::nodes::Nodes::Remap(_) => {
+                type $ty = $crate::nodes::math::RemapNode;
+                $body
+            }
+            $crate::nodes::Nodes::Sink(_) => {
+                type $ty = $crate::nodes::sink::SinkNode;
+                $body
+            }
+            $crate::nodes::Nodes::Range(_) => {
+                type $ty = $crate::nodes::range::RangeNode;
+                $body
+            }
+            $crate::nodes::Nodes::Point(_) => {
+                type $ty = $crate::nodes::point::PointNode;
+                $body
+            }
+            $crate::nodes::Nodes::DeconstructPoint(_) => {
+                type $ty = $crate::nodes::point::DeconstructPointNode;
+                $body
+            }
+            $crate::nodes::Nodes::Circle(_) => {
+                type $ty = $crate::nodes::circle::CircleNode;
+                $body
+            }
+            $crate::nodes::Nodes::Rectangle(_) => {
+                type $ty = $crate::nodes::primitive::RectangleNode;
+                $body
+            }
+            $crate::nodes::Nodes::RoundedRectangle(_) => {
+                type $ty = $crate::nodes::primitive::RoundedRectangleNode;
+                $body
+            }
+            $crate::nodes::Nodes::Ellipse(_) => {
+                type $ty = $crate::nodes::primitive::EllipseNode;
+                $body
+            }
+            $crate::nodes::Nodes::Line(_) => {
+                type $ty = $crate::nodes::primitive::LineNode;
+                $body
+            }
+            $crate::nodes::Nodes::Arc(_) => {
+                type $ty = $crate::nodes::primitive::ArcNode;
+                $body
+            }
+            $crate::nodes::Nodes::Polyline(_) => {
+                type $ty = $crate::nodes::primitive::PolylineNode;
+                $body
+            }
+            $crate::nodes::Nodes::Polygon(_) => {
+                type $ty = $crate::nodes::primitive::PolygonNode;
+                $body
+            }
+            $crate::nodes::Nodes::Bezier(_) => {
+                type $ty = $crate::nodes::curve::BezierNode;
+                $body
+            }
+            $crate::nodes::Nodes::CatmullRom(_) => {
+                type $ty = $crate::nodes::curve::CatmullRomNode;
+                $body
+            }
+            $crate::nodes::Nodes::Spline(_) => {
+                type $ty = $crate::nodes::curve::SplineNode;
+                $body
+            }
+            $crate::nodes::Nodes::Nurbs(_) => {
+                type $ty = $crate::nodes::curve::NurbsNode;
+                $body
+            }
+            $crate::nodes::Nodes::Translate(_) => {
+                type $ty = $crate::nodes::transform::TranslateNode;
+                $body
+            }
+            $crate::nodes::Nodes::Rotate(_) => {
+                type $ty = $crate::nodes::transform::RotateNode;
+                $body
+            }
+            $crate::nodes::Nodes::Scale(_) => {
+                type $ty = $crate::nodes::transform::ScaleNode;
+                $body
+            }
+            $crate::nodes::Nodes::Mirror(_) => {
+                type $ty = $crate::nodes::transform::MirrorNode;
+                $body
+            }
+            $crate::nodes::Nodes::Compose(_) => {
+                type $ty = $crate::nodes::transform::ComposeNode;
+                $body
+            }
+            $crate::nodes::Nodes::Canvas(_) => {
+                type $ty = $crate::nodes::canvas::CanvasNode;
+                $body
+            }
+            $crate::nodes::Nodes::RepeatShape(_) => {
+                type $ty = $crate::nodes::repeat::RepeatShapeNode;
+                $body
+            }
+            $crate::nodes::Nodes::PolarArray(_) => {
+                type $ty = $crate::nodes::repeat::PolarArrayNode;
+                $body
+            }
+            $crate::nodes::Nodes::LinearArray(_) => {
+                type $ty = $crate::nodes::repeat::LinearArrayNode;
+                $body
+            }
+            $crate::nodes::Nodes::GridArray(_) => {
+                type $ty = $crate::nodes::repeat::GridArrayNode;
+                $body
+            }
+            $crate::nodes::Nodes::Graft(_) => {
+                type $ty = $crate::nodes::tree::GraftNode;
+                $body
+            }
+            $crate::nodes::Nodes::Flatten(_) => {
+                type $ty = $crate::nodes::tree::FlattenNode;
+                $body
+            }
+            $crate::nodes::Nodes::Simplify(_) => {
+                type $ty = $crate::nodes::tree::SimplifyNode;
+                $body
+            }
+            $crate::nodes::Nodes::PathMapper(_) => {
+                type $ty = $crate::nodes::tree::PathMapperNode;
+                $body
+            }
+            $crate::nodes::Nodes::BranchItem(_) => {
+                type $ty = $crate::nodes::tree::BranchItemNode;
+                $body
+            }
+            $crate::nodes::Nodes::RgbColor(_) => {
+                type $ty = $crate::nodes::color::RgbColorNode;
+                $body
+            }
+            $crate::nodes::Nodes::HsvColor(_) => {
+                type $ty = $crate::nodes::color::HsvColorNode;
+                $body
+            }
+            $crate::nodes::Nodes::OklchColor(_) => {
+                type $ty = $crate::nodes::color::OklchColorNode;
+                $body
+            }
+            $crate::nodes::Nodes::HexColor(_) => {
+                type $ty = $crate::nodes::color::HexColorNode;
+                $body
+            }
+            $crate::nodes::Nodes::Gradient(_) => {
+                type $ty = $crate::nodes::color::GradientNode;
+                $body
+            }
+            $crate::nodes::Nodes::Palette(_) => {
+                type $ty = $crate::nodes::color::PaletteNode;
+                $body
+            }
+            $crate::nodes::Nodes::Harmony(_) => {
+                type $ty = $crate::nodes::color::HarmonyNode;
+                $body
+            }
+            $crate::nodes::Nodes::Style(_) => {
+                type $ty = $crate::nodes::style::StyleNode;
+                $body
+            }
+            $crate::nodes::Nodes::Parameter(_) => {
+                type $ty = $crate::nodes::parameter::ParameterNode;
+                $body
+            }
+            $crate::nodes::Nodes::Output(_) => {
+                type $ty = $crate::nodes::parameter::GraphOutputNode;
+                $body
+            }
+        }
+    };
+    ($nodes:expr, $node:ident => $body:expr) => {
+        match $nodes {
+            $crate::nodes::Nodes::ConstantValueNode($node) => $body,
+            $crate::nodes::Nodes::Math($node) => $body,
+            $crate::nodes::Nodes::Function($node) => $body,
+            $crate::nodes::Nodes::Clamp($node) => $body,
+            $crate::nodes::Nodes::Lerp($node) => $body,
+            $crate::nodes::Nodes::Remap($node) => $body,
+            $crate::nodes::Nodes::Sink($node) => $body,
+            $crate::nodes::Nodes::Range($node) => $body,
+            $crate::nodes::Nodes::Point($node) => $body,
+            $crate::nodes::Nodes::DeconstructPoint($node) => $body,
+            $crate::nodes::Nodes::Circle($node) => $body,
+            $crate::nodes::Nodes::Rectangle($node) => $body,
+            $crate::nodes::Nodes::RoundedRectangle($node) => $body,
+            $crate::nodes::Nodes::Ellipse($node) => $body,
+            $crate::nodes::Nodes::Line($node) => $body,
+            $crate::nodes::Nodes::Arc($node) => $body,
+            $crate::nodes::Nodes::Polyline($node) => $body,
+            $crate::nodes::Nodes::Polygon($node) => $body,
+            $crate::nodes::Nodes::Bezier($node) => $body,
+            $crate::nodes::Nodes::CatmullRom($node) => $body,
+            $crate::nodes::Nodes::Spline($node) => $body,
+            $crate::nodes::Nodes::Nurbs($node) => $body,
+            $crate::nodes::Nodes::Translate($node) => $body,
+            $crate::nodes::Nodes::Rotate($node) => $body,
+            $crate::nodes::Nodes::Scale($node) => $body,
+            $crate::nodes::Nodes::Mirror($node) => $body,
+            $crate::nodes::Nodes::Compose($node) => $body,
+            $crate::nodes::Nodes::Canvas($node) => $body,
+            $crate::nodes::Nodes::RepeatShape($node) => $body,
+            $crate::nodes::Nodes::PolarArray($node) => $body,
+            $crate::nodes::Nodes::LinearArray($node) => $body,
+            $crate::nodes::Nodes::GridArray($node) => $body,
+            $crate::nodes::Nodes::Graft($node) => $body,
+            $crate::nodes::Nodes::Flatten($node) => $body,
+            $crate::nodes::Nodes::Simplify($node) => $body,
+            $crate::nodes::Nodes::PathMapper($node) => $body,
+            $crate::nodes::Nodes::BranchItem($node) => $body,
+            $crate::nodes::Nodes::RgbColor($node) => $body,
+            $crate::nodes::Nodes::HsvColor($node) => $body,
+            $crate::nodes::Nodes::OklchColor($node) => $body,
+            $crate::nodes::Nodes::HexColor($node) => $body,
+            $crate::nodes::Nodes::Gradient($node) => $body,
+            $crate::nodes::Nodes::Palette($node) => $body,
+            $crate::nodes::Nodes::Harmony($node) => $body,
+            $crate::nodes::Nodes::Style($node) => $body,
+            $crate::nodes::Nodes::Parameter($node) => $body,
+            $crate::nodes::Nodes::Output($node) => $body,
+        }
+    };
+}
 
 /// Main enum containing all node types
 #[derive(serde::Serialize, serde::Deserialize)]
 pub enum Nodes {
     ConstantValueNode(constant_value::ConstantValueNode),
+    Math(math::MathNode),
+    Function(math::FunctionNode),
+    Clamp(math::ClampNode),
+    Lerp(math::LerpNode),
+    Remap(math::RemapNode),
     Sink(sink::SinkNode),
     Range(range::RangeNode),
     Point(point::PointNode),
+    DeconstructPoint(point::DeconstructPointNode),
     Circle(circle::CircleNode),
+    Rectangle(primitive::RectangleNode),
+    RoundedRectangle(primitive::RoundedRectangleNode),
+    Ellipse(primitive::EllipseNode),
+    Line(primitive::LineNode),
+    Arc(primitive::ArcNode),
+    Polyline(primitive::PolylineNode),
+    Polygon(primitive::PolygonNode),
+    Bezier(curve::BezierNode),
+    CatmullRom(curve::CatmullRomNode),
+    Spline(curve::SplineNode),
+    Nurbs(curve::NurbsNode),
+    Translate(transform::TranslateNode),
+    Rotate(transform::RotateNode),
+    Scale(transform::ScaleNode),
+    Mirror(transform::MirrorNode),
+    Compose(transform::ComposeNode),
     Canvas(canvas::CanvasNode),
     RepeatShape(repeat::RepeatShapeNode),
+    PolarArray(repeat::PolarArrayNode),
+    LinearArray(repeat::LinearArrayNode),
+    GridArray(repeat::GridArrayNode),
+    Graft(tree::GraftNode),
+    Flatten(tree::FlattenNode),
+    Simplify(tree::SimplifyNode),
+    PathMapper(tree::PathMapperNode),
+    BranchItem(tree::BranchItemNode),
+    RgbColor(color::RgbColorNode),
+    HsvColor(color::HsvColorNode),
+    OklchColor(color::OklchColorNode),
+    HexColor(color::HexColorNode),
+    Gradient(color::GradientNode),
+    Palette(color::PaletteNode),
+    Harmony(color::HarmonyNode),
+    Style(style::StyleNode),
+    Parameter(parameter::ParameterNode),
+    Output(parameter::GraphOutputNode),
 }
+
+/// A node which can be put into a wire to convert values of one
+/// type into another, for types which don't convert implicitly.
+/// Returns the node together with the output to wire onwards and
+/// the name of that output, as it may only pass on part of the value.
+///
+/// Types converting implicitly, as listed on [`Value`], are wired
+/// directly. Of all others, only points turn into numbers so far
+pub fn conversion(from: ValueType, to: ValueType) -> Option<(Nodes, usize, &'static str)> {
+    match (from, to) {
+        (ValueType::Point, ValueType::Number) => Some((
+            Nodes::DeconstructPoint(point::DeconstructPointNode::default()),
+            0,
+            "X",
+        )),
+        _ => None,
+    }
+}
+
 pub fn format_float(value: f64) -> String {
     let value = (value * 1000.0).round() / 1000.0;
     format!("{}", value)
@@ -36,106 +352,124 @@ pub fn format_point(value: piet::kurbo::Point) -> String {
     let y = (value.y * 1000.0).round() / 1000.0;
     format!("({};{})", x, y)
 }
+/// Shows the value arriving over the wires of an input pin,
+/// or an editor for the nodes own value if nothing is connected
 pub fn show_number_input(
     title: impl AsRef<str>,
     pin: &InPin,
     ui: &mut Ui,
-    scale: f32,
+    _scale: f32,
     snarl: &mut Snarl<Nodes>,
+    results: &Results,
     update_fn: impl FnOnce(egui_snarl::InPinId, &mut Snarl<Nodes>) -> &mut f64,
 ) -> PinInfo {
     ui.label(title.as_ref());
-    match &*pin.remotes {
-        [] => {
-            ui.add(egui::DragValue::new(update_fn(pin.id, snarl)));
-            PinInfo::square().with_fill(crate::NUMBER_COLOR)
-        }
-        [remote] => {
-            if let Some(value) = snarl[remote.node].try_get_float() {
-                *(update_fn(pin.id, snarl)) = value;
-                ui.label(format_float(value));
-                PinInfo::square().with_fill(crate::NUMBER_COLOR)
-            } else {
-                ui.add(egui::DragValue::new(update_fn(pin.id, snarl)));
-                PinInfo::square().with_fill(crate::NUMBER_COLOR)
-            }
-        }
-        _ => unreachable!(),
+    if pin.remotes.is_empty() {
+        ui.add(egui::DragValue::new(update_fn(pin.id, snarl)));
+    } else {
+        show_remote_value(ui, results.input(pin));
     }
+    PinInfo::square().with_fill(crate::NUMBER_COLOR)
 }
 
 pub fn show_point_input(
     title: impl AsRef<str>,
     pin: &InPin,
     ui: &mut Ui,
-    scale: f32,
+    _scale: f32,
     snarl: &mut Snarl<Nodes>,
+    results: &Results,
     update_fn: impl FnOnce(egui_snarl::InPinId, &mut Snarl<Nodes>) -> &mut piet::kurbo::Point,
 ) -> PinInfo {
     ui.label(title.as_ref());
-    match &*pin.remotes {
-        [] => PinInfo::square().with_fill(crate::POINT_COLOR),
-        [remote] => {
-            if let Some(value) = snarl[remote.node].try_get_point() {
-                *(update_fn(pin.id, snarl)) = value;
-                ui.label(format_point(value));
-                PinInfo::square().with_fill(crate::POINT_COLOR)
-            } else {
-                PinInfo::square().with_fill(crate::POINT_COLOR)
-            }
+    if pin.remotes.is_empty() {
+        let point = update_fn(pin.id, snarl);
+        ui.add(egui::DragValue::new(&mut point.x));
+        ui.add(egui::DragValue::new(&mut point.y));
+    } else {
+        show_remote_value(ui, results.input(pin));
+    }
+    PinInfo::square().with_fill(crate::POINT_COLOR)
+}
+
+pub fn show_color_input(
+    title: impl AsRef<str>,
+    pin: &InPin,
+    ui: &mut Ui,
+    _scale: f32,
+    snarl: &mut Snarl<Nodes>,
+    results: &Results,
+    update_fn: impl FnOnce(egui_snarl::InPinId, &mut Snarl<Nodes>) -> &mut crate::color::Color,
+) -> PinInfo {
+    ui.label(title.as_ref());
+    if pin.remotes.is_empty() {
+        let color = update_fn(pin.id, snarl);
+        let mut rgba = [color.r, color.g, color.b, color.a].map(|c| c as f32);
+        if ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed() {
+            let [r, g, b, a] = rgba.map(f64::from);
+            *color = crate::color::Color::rgba(r, g, b, a);
         }
-        _ => unreachable!(),
+    } else {
+        show_remote_value(ui, results.input(pin));
     }
+    PinInfo::square().with_fill(crate::COLOR_COLOR)
 }
 
 pub fn show_shape_input(
     title: impl AsRef<str>,
     pin: &InPin,
     ui: &mut Ui,
-    scale: f32,
+    _scale: f32,
+    results: &Results,
+) -> PinInfo {
+    ui.label(title.as_ref());
+    if !pin.remotes.is_empty() {
+        show_remote_value(ui, results.input(pin));
+    }
+    PinInfo::square().with_fill(crate::SHAPE_COLOR)
+}
+
+pub fn show_text_input(
+    title: impl AsRef<str>,
+    pin: &InPin,
+    ui: &mut Ui,
+    _scale: f32,
     snarl: &mut Snarl<Nodes>,
-    update_fn: impl FnOnce(egui_snarl::InPinId, &mut Snarl<Nodes>) -> &mut Shapes,
+    results: &Results,
+    update_fn: impl FnOnce(egui_snarl::InPinId, &mut Snarl<Nodes>) -> &mut String,
 ) -> PinInfo {
     ui.label(title.as_ref());
-    match &*pin.remotes {
-        [] => PinInfo::square().with_fill(crate::SHAPE_COLOR),
-        [remote] => {
-            if let Some(value) = snarl[remote.node].try_get_shape() {
-                *(update_fn(pin.id, snarl)) = value;
-                PinInfo::square().with_fill(crate::SHAPE_COLOR)
-            } else {
-                PinInfo::square().with_fill(crate::SHAPE_COLOR)
-            }
-        }
-        _ => unreachable!(),
-    }
-}
-
-// pub fn show_shapes_input(
-//     title: impl AsRef<str>,
-//     pin: &InPin,
-//     ui: &mut Ui,
-//     scale: f32,
-//     snarl: &mut Snarl<Nodes>,
-//     update_fn: impl FnOnce(egui_snarl::InPinId, &mut Snarl<Nodes>) -> &mut Vec<Shapes>,
-// ) -> PinInfo {
-//     ui.label(title.as_ref());
-//     match &*pin.remotes {
-//         [] => PinInfo::square().with_fill(crate::SHAPE_COLOR),
-//         [remote] => {
-//             if let Some(value) = snarl[remote.node].try_get_point() {
-//                 *(update_fn(pin.id, snarl)) = value;
-//                 ui.label(format_point(value));
-//                 PinInfo::square().with_fill(crate::SHAPE_COLOR)
-//             } else {
-//                 PinInfo::square().with_fill(crate::SHAPE_COLOR)
-//             }
-//         }
-//         _ => unreachable!(),
-//     }
-// }
-
-fn get_node_mut<'a, N>(snarl: &'a mut egui_snarl::Snarl<Nodes>, id: egui_snarl::NodeId) -> &'a mut N
+    if pin.remotes.is_empty() {
+        ui.add(egui::TextEdit::singleline(update_fn(pin.id, snarl)).desired_width(80.0));
+    } else {
+        show_remote_value(ui, results.input(pin));
+    }
+    PinInfo::square()
+}
+
+/// Input pin for data of any type, which has no value of its own
+pub fn show_data_input(
+    title: impl AsRef<str>,
+    pin: &InPin,
+    ui: &mut Ui,
+    _scale: f32,
+    results: &Results,
+) -> PinInfo {
+    ui.label(title.as_ref());
+    if !pin.remotes.is_empty() {
+        show_remote_value(ui, results.input(pin));
+    }
+    PinInfo::circle()
+}
+
+fn show_remote_value(ui: &mut Ui, value: Option<&Value>) {
+    match value {
+        Some(value) => ui.label(value.to_string()),
+        None => ui.label("None"),
+    };
+}
+
+fn get_node_mut<N>(snarl: &mut egui_snarl::Snarl<Nodes>, id: egui_snarl::NodeId) -> &mut N
 where
     N: NodeDowncast,
 {
@@ -154,66 +488,45 @@ pub trait NodeDowncast: Node {
 
 impl Nodes {
     pub fn inputs(&self) -> usize {
-        match self {
-            Self::ConstantValueNode(_) => ConstantValueNode::inputs(),
-            Self::Sink(_) => SinkNode::inputs(),
-            Self::Range(_) => RangeNode::inputs(),
-            Self::Point(_) => PointNode::inputs(),
-            Self::Circle(_) => CircleNode::inputs(),
-            Self::Canvas(_) => CanvasNode::inputs(),
-            Self::RepeatShape(_) => RepeatShapeNode::inputs(),
-        }
+        dispatch!(self, type N => N::inputs())
     }
     pub fn outputs(&self) -> usize {
-        match self {
-            Self::ConstantValueNode(_) => ConstantValueNode::outputs(),
-            Self::Sink(_) => SinkNode::outputs(),
-            Self::Range(_) => RangeNode::outputs(),
-            Self::Point(_) => PointNode::outputs(),
-            Self::Circle(_) => CircleNode::outputs(),
-            Self::Canvas(_) => CanvasNode::outputs(),
-            Self::RepeatShape(_) => RepeatShapeNode::outputs(),
-        }
+        dispatch!(self, type N => N::outputs())
     }
     pub fn title(&self) -> String {
         match self {
-            Self::ConstantValueNode(_) => ConstantValueNode::title(),
-            Self::Sink(_) => SinkNode::title(),
-            Self::Range(_) => RangeNode::title(),
-            Self::Point(_) => PointNode::title(),
-            Self::Circle(_) => CircleNode::title(),
-            Self::Canvas(_) => CanvasNode::title(),
-            Self::RepeatShape(_) => RepeatShapeNode::title(),
+            // Named after the operation chosen by the user
+            Nodes::Math(node) => node.operation.name().to_string(),
+            Nodes::Function(node) => node.function.name().to_string(),
+            _ => dispatch!(self, type N => N::title()),
         }
     }
-    pub fn try_get_float(&self) -> Option<f64> {
-        match self {
-            Self::ConstantValueNode(node) => Some(node.number_out()),
-            Self::Range(node) => node.get_numbers().next(),
-            _ => None,
-        }
+    pub fn input_type(&self, index: usize) -> ValueType {
+        dispatch!(self, type N => N::input_type(index))
     }
-    pub fn try_get_point(&self) -> Option<piet::kurbo::Point> {
+    pub fn output_type(&self, index: usize) -> ValueType {
         match self {
-            Self::Point(node) => Some(node.point_out()),
-            _ => None,
+            // The only node whose type is chosen by the user
+            Nodes::Parameter(node) => node.kind,
+            _ => dispatch!(self, type N => N::output_type(index)),
         }
     }
-
-    pub fn try_get_shape(&self) -> Option<Shapes> {
-        match self {
-            Self::Circle(node) => Some(node.shape_out()),
-            Self::RepeatShape(node) => Some(node.value_out()),
-            _ => None,
-        }
+    /// The value of an unconnected input pin, `None` if
+    /// the node has no value of its own for it
+    pub fn default_input(&self, index: usize) -> Option<Value> {
+        dispatch!(self, node => node.default_input(index))
     }
-
-    pub fn try_get_shapes(&self) -> Option<Vec<Shapes>> {
-        match self {
-            Self::RepeatShape(node) => Some(node.values_out().collect::<Vec<_>>()),
-            Self::Circle(node) => Some(node.values_out().collect::<Vec<_>>()),
-            _ => None,
-        }
+    pub fn input_access(&self, index: usize) -> Access {
+        dispatch!(self, node => node.input_access(index))
+    }
+    pub fn matching(&self) -> ListMatching {
+        dispatch!(self, node => node.matching())
+    }
+    pub fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        dispatch!(self, node => node.matching_mut())
+    }
+    pub fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        dispatch!(self, node => node.solve(inputs))
     }
 }
 
@@ -223,24 +536,77 @@ pub trait NodeInfo: Node {
     fn inputs() -> usize;
     fn outputs() -> usize;
     fn title() -> String;
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Any
+    }
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Any
+    }
 }
 
 pub trait InputNode<T>: Node {
-    fn show_input(pin: &InPin, ui: &mut Ui, scale: f32, snarl: &mut Snarl<T>) -> PinInfo;
+    fn show_input(
+        pin: &InPin,
+        ui: &mut Ui,
+        scale: f32,
+        snarl: &mut Snarl<T>,
+        results: &Results,
+    ) -> PinInfo;
 }
 
 pub trait OutputNode<T>: Node {
-    fn show_output(pin: &OutPin, ui: &mut Ui, scale: f32, snarl: &mut Snarl<T>) -> PinInfo;
+    fn show_output(
+        pin: &OutPin,
+        ui: &mut Ui,
+        scale: f32,
+        snarl: &mut Snarl<T>,
+        results: &Results,
+    ) -> PinInfo;
 }
 
-pub trait NumberEmitterNode {
-    fn try_get_number(&self) -> f64;
-    fn try_get_numbers(&self) -> impl Iterator<Item = f64> + '_;
-    // TODO: Maybe something to broadcast numbers?
-    // Or, alternatively, nodes can decide that on their inputs
-    // themselfes
+/// Headless evaluation of a node, independent of any ui
+pub trait SolveNode: Node {
+    /// The value an input pin takes when no wire is connected to it
+    fn default_input(&self, index: usize) -> Option<Value>;
+    /// Calculates the values of all output pins from the values on
+    /// the input pins
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError>;
+    /// How the input pin at `index` consumes lists
+    fn input_access(&self, _index: usize) -> Access {
+        Access::Item
+    }
+    /// How lists arriving at multiple item inputs are matched up,
+    /// nodes storing their own strategy also hand it out mutably
+    fn matching(&self) -> ListMatching {
+        ListMatching::default()
+    }
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        None
+    }
 }
-pub trait EmitterNode<T> {
-    fn value_out(&self) -> T;
-    fn values_out(&self) -> impl Iterator<Item = T> + '_;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversion_nodes_fill_in_for_missing_implicit_conversions() {
+        use ValueType::*;
+        let types = [
+            Any, Number, Bool, Text, Point, Color, Shape, Curve, Transform, Drawing,
+        ];
+        let mut converted = Vec::new();
+        for from in types {
+            for to in types {
+                let Some((node, output, _)) = conversion(from, to) else {
+                    continue;
+                };
+                assert!(!from.converts_to(to), "{from:?} converts to {to:?} anyway");
+                assert!(from.converts_to(node.input_type(0)));
+                assert_eq!(node.output_type(output), to);
+                converted.push((from, to));
+            }
+        }
+        assert_eq!(converted, vec![(Point, Number)]);
+    }
 }
@@ -0,0 +1,301 @@
+use egui_snarl::ui::PinInfo;
+
+use crate::{
+    color::Color,
+    solver::{Access, EvalError, Results},
+    value::{Value, ValueType},
+};
+
+/// Named input of a graph, which can be set from outside of the editor.
+/// Unless it is set, the parameter emits its default value
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ParameterNode {
+    pub name: String,
+    pub kind: ValueType,
+    pub default: Value,
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl Default for ParameterNode {
+    fn default() -> Self {
+        Self {
+            name: "parameter".to_string(),
+            kind: ValueType::Number,
+            default: Value::Number(0.0),
+            min: None,
+            max: None,
+            description: String::new(),
+        }
+    }
+}
+
+impl ParameterNode {
+    /// Types a parameter can have, these all have a default value
+    pub const KINDS: [ValueType; 5] = [
+        ValueType::Number,
+        ValueType::Bool,
+        ValueType::Text,
+        ValueType::Point,
+        ValueType::Color,
+    ];
+
+    /// Checks if a value can be used for this parameter, converting
+    /// it to the parameter type and checking the number range
+    pub fn validate(&self, value: &Value) -> Result<Value, EvalError> {
+        let value = value.convert(self.kind)?;
+        if let Value::Number(number) = value {
+            if self.min.is_some_and(|min| number < min) || self.max.is_some_and(|max| number > max)
+            {
+                return Err(EvalError::Domain(format!(
+                    "{} has to be in {}, but is {number}",
+                    self.name,
+                    self.range_text()
+                )));
+            }
+        }
+        Ok(value)
+    }
+
+    pub fn range_text(&self) -> String {
+        let bound = |bound: Option<f64>| bound.map(super::format_float).unwrap_or_default();
+        format!("[{}..{}]", bound(self.min), bound(self.max))
+    }
+
+    /// Changes the type, resetting the default value if it doesn't convert
+    pub fn set_kind(&mut self, kind: ValueType) {
+        self.kind = kind;
+        self.default = self
+            .default
+            .convert(kind)
+            .ok()
+            .or_else(|| kind.default_value())
+            .expect("Parameter kinds have a default value");
+    }
+
+    /// Editor for the default value, respecting the number range
+    pub fn show_value_editor(&mut self, ui: &mut egui::Ui) {
+        match &mut self.default {
+            Value::Number(number) => {
+                let min = self.min.unwrap_or(f64::NEG_INFINITY);
+                let max = self.max.unwrap_or(f64::INFINITY);
+                ui.add(egui::DragValue::new(number).clamp_range(min..=max));
+            }
+            Value::Bool(value) => {
+                ui.checkbox(value, "");
+            }
+            Value::Text(text) => {
+                ui.add(egui::TextEdit::singleline(text).desired_width(80.0));
+            }
+            Value::Point(point) => {
+                ui.add(egui::DragValue::new(&mut point.x));
+                ui.add(egui::DragValue::new(&mut point.y));
+            }
+            Value::Color(color) => {
+                let mut rgba = [color.r, color.g, color.b, color.a].map(|channel| channel as f32);
+                if ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed() {
+                    let [r, g, b, a] = rgba.map(f64::from);
+                    *color = Color::rgba(r, g, b, a);
+                }
+            }
+            value => {
+                ui.label(value.to_string());
+            }
+        }
+    }
+
+    /// Editor for everything except the default value,
+    /// which is shown in the context menu of the node
+    pub fn show_settings(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("parameter_settings").show(ui, |ui| {
+            ui.label("Type");
+            egui::ComboBox::from_id_source("parameter_kind")
+                .selected_text(self.kind.name())
+                .show_ui(ui, |ui| {
+                    for kind in Self::KINDS {
+                        if ui
+                            .selectable_label(self.kind == kind, kind.name())
+                            .clicked()
+                        {
+                            self.set_kind(kind);
+                        }
+                    }
+                });
+            ui.end_row();
+
+            if self.kind == ValueType::Number {
+                for (label, bound) in [("Min", &mut self.min), ("Max", &mut self.max)] {
+                    ui.label(label);
+                    ui.horizontal(|ui| {
+                        let mut enabled = bound.is_some();
+                        if ui.checkbox(&mut enabled, "").changed() {
+                            *bound = enabled.then_some(0.0);
+                        }
+                        if let Some(bound) = bound {
+                            ui.add(egui::DragValue::new(bound));
+                        }
+                    });
+                    ui.end_row();
+                }
+            }
+
+            ui.label("Description");
+            ui.text_edit_multiline(&mut self.description);
+            ui.end_row();
+        });
+    }
+}
+
+impl super::Node for ParameterNode {}
+impl super::NodeInfo for ParameterNode {
+    fn inputs() -> usize {
+        0
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Parameter".to_string()
+    }
+}
+impl super::NodeDowncast for ParameterNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Parameter(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Parameter(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for ParameterNode {
+    fn show_input(
+        _pin: &egui_snarl::InPin,
+        _ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        unreachable!()
+    }
+}
+impl super::OutputNode<super::Nodes> for ParameterNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        let node = super::get_node_mut::<Self>(snarl, pin.id.node);
+        ui.add(egui::TextEdit::singleline(&mut node.name).desired_width(80.0));
+        node.show_value_editor(ui);
+        PinInfo::square().with_fill(node.kind.color())
+    }
+}
+impl super::SolveNode for ParameterNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        None
+    }
+
+    fn solve(&self, _inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        Ok(vec![self.validate(&self.default)?])
+    }
+}
+
+/// Named result of a graph, which is handed out when
+/// the graph is solved from outside of the editor
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct GraphOutputNode {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl Default for GraphOutputNode {
+    fn default() -> Self {
+        Self {
+            name: "output".to_string(),
+            description: String::new(),
+        }
+    }
+}
+
+impl super::Node for GraphOutputNode {}
+impl super::NodeInfo for GraphOutputNode {
+    fn inputs() -> usize {
+        1
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Output".to_string()
+    }
+}
+impl super::NodeDowncast for GraphOutputNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Output(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Output(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for GraphOutputNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        let node = super::get_node_mut::<Self>(snarl, pin.id.node);
+        ui.add(egui::TextEdit::singleline(&mut node.name).desired_width(80.0));
+        super::show_data_input("", pin, ui, scale, results)
+    }
+}
+impl super::OutputNode<super::Nodes> for GraphOutputNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        ui.label("Value");
+        PinInfo::circle()
+    }
+}
+impl super::SolveNode for GraphOutputNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        None
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        Ok(vec![inputs[0].clone()])
+    }
+
+    fn input_access(&self, _index: usize) -> Access {
+        Access::Tree
+    }
+}
@@ -1,8 +1,15 @@
 use egui_snarl::ui::PinInfo;
 
+use crate::{
+    solver::{EvalError, ListMatching, Results},
+    value::{Value, ValueType},
+};
+
 #[derive(serde::Serialize, serde::Deserialize, Default)]
 pub struct PointNode {
     point: piet::kurbo::Point,
+    #[serde(default)]
+    matching: ListMatching,
 }
 
 impl PointNode {
@@ -24,11 +31,22 @@ impl super::NodeInfo for PointNode {
     fn title() -> String {
         "Point".to_string()
     }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Point
+    }
 }
 
 impl super::NodeDowncast for PointNode {
     fn try_downcast(from: &super::Nodes) -> Option<&Self> {
-        todo!()
+        match from {
+            super::Nodes::Point(node) => Some(node),
+            _ => None,
+        }
     }
 
     fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
@@ -45,12 +63,13 @@ impl super::InputNode<super::Nodes> for PointNode {
         ui: &mut egui::Ui,
         scale: f32,
         snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
     ) -> egui_snarl::ui::PinInfo {
         match pin.id.input {
-            0 => super::show_number_input("X", pin, ui, scale, snarl, |id, snarl| {
+            0 => super::show_number_input("X", pin, ui, scale, snarl, results, |id, snarl| {
                 &mut super::get_node_mut::<Self>(snarl, id.node).point.x
             }),
-            1 => super::show_number_input("Y", pin, ui, scale, snarl, |id, snarl| {
+            1 => super::show_number_input("Y", pin, ui, scale, snarl, results, |id, snarl| {
                 &mut super::get_node_mut::<Self>(snarl, id.node).point.y
             }),
             _ => unreachable!(),
@@ -60,12 +79,130 @@ impl super::InputNode<super::Nodes> for PointNode {
 
 impl super::OutputNode<super::Nodes> for PointNode {
     fn show_output(
-        pin: &egui_snarl::OutPin,
+        _pin: &egui_snarl::OutPin,
         ui: &mut egui::Ui,
-        scale: f32,
-        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
     ) -> egui_snarl::ui::PinInfo {
         ui.label("Point");
         PinInfo::circle().with_fill(crate::POINT_COLOR)
     }
 }
+
+impl super::SolveNode for PointNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Number(self.point.x)),
+            1 => Some(Value::Number(self.point.y)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let x = inputs[0].as_number()?;
+        let y = inputs[1].as_number()?;
+        Ok(vec![Value::Point(piet::kurbo::Point::new(x, y))])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Splits a point into its coordinates
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+pub struct DeconstructPointNode {
+    point: piet::kurbo::Point,
+}
+
+impl super::Node for DeconstructPointNode {}
+impl super::NodeInfo for DeconstructPointNode {
+    fn inputs() -> usize {
+        1
+    }
+
+    fn outputs() -> usize {
+        2
+    }
+
+    fn title() -> String {
+        "Deconstruct Point".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Point
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+}
+
+impl super::NodeDowncast for DeconstructPointNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::DeconstructPoint(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::DeconstructPoint(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl super::InputNode<super::Nodes> for DeconstructPointNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> egui_snarl::ui::PinInfo {
+        match pin.id.input {
+            0 => super::show_point_input("Point", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).point
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl super::OutputNode<super::Nodes> for DeconstructPointNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> egui_snarl::ui::PinInfo {
+        match pin.id.output {
+            0 => ui.label("X"),
+            1 => ui.label("Y"),
+            _ => unreachable!(),
+        };
+        PinInfo::square().with_fill(crate::NUMBER_COLOR)
+    }
+}
+
+impl super::SolveNode for DeconstructPointNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(self.point)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let point = inputs[0].as_point()?;
+        Ok(vec![Value::Number(point.x), Value::Number(point.y)])
+    }
+}
@@ -0,0 +1,891 @@
+use egui_snarl::ui::PinInfo;
+use piet::kurbo::{self, BezPath, Point};
+
+use crate::{
+    shapes::Shapes,
+    solver::{Access, EvalError, ListMatching, Results},
+    value::{Value, ValueType},
+};
+
+fn show_shape_output(title: &str, ui: &mut egui::Ui) -> PinInfo {
+    ui.label(title);
+    PinInfo::triangle().with_fill(crate::SHAPE_COLOR)
+}
+
+/// Reads a length, which can't be negative
+fn length(name: &str, value: &Value) -> Result<f64, EvalError> {
+    let length = value.as_number()?;
+    if length < 0.0 {
+        return Err(EvalError::Domain(format!(
+            "{name} can't be negative, but is {length}"
+        )));
+    }
+    Ok(length)
+}
+
+/// Axis aligned rectangle from its top left corner and size
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RectangleNode {
+    corner: Point,
+    width: f64,
+    height: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for RectangleNode {
+    fn default() -> Self {
+        Self {
+            corner: Point::ZERO,
+            width: 20.0,
+            height: 10.0,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for RectangleNode {}
+impl super::NodeInfo for RectangleNode {
+    fn inputs() -> usize {
+        3
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Rectangle".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Point,
+            _ => ValueType::Number,
+        }
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
+}
+impl super::NodeDowncast for RectangleNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Rectangle(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Rectangle(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for RectangleNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_point_input("Corner", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).corner
+            }),
+            1 => super::show_number_input("Width", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).width
+            }),
+            2 => super::show_number_input("Height", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).height
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for RectangleNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_shape_output("Rectangle", ui)
+    }
+}
+impl super::SolveNode for RectangleNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(self.corner)),
+            1 => Some(Value::Number(self.width)),
+            2 => Some(Value::Number(self.height)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let corner = inputs[0].as_point()?;
+        let width = length("Width", &inputs[1])?;
+        let height = length("Height", &inputs[2])?;
+        let rect = kurbo::Rect::from_origin_size(corner, (width, height));
+        Ok(vec![Value::Shape(Shapes::Rect(rect))])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Rectangle with all corners rounded by the same radius
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RoundedRectangleNode {
+    corner: Point,
+    width: f64,
+    height: f64,
+    radius: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for RoundedRectangleNode {
+    fn default() -> Self {
+        Self {
+            corner: Point::ZERO,
+            width: 20.0,
+            height: 10.0,
+            radius: 2.0,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for RoundedRectangleNode {}
+impl super::NodeInfo for RoundedRectangleNode {
+    fn inputs() -> usize {
+        4
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Rounded Rectangle".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Point,
+            _ => ValueType::Number,
+        }
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
+}
+impl super::NodeDowncast for RoundedRectangleNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::RoundedRectangle(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::RoundedRectangle(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for RoundedRectangleNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_point_input("Corner", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).corner
+            }),
+            1 => super::show_number_input("Width", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).width
+            }),
+            2 => super::show_number_input("Height", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).height
+            }),
+            3 => super::show_number_input("Radius", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).radius
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for RoundedRectangleNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_shape_output("Rectangle", ui)
+    }
+}
+impl super::SolveNode for RoundedRectangleNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(self.corner)),
+            1 => Some(Value::Number(self.width)),
+            2 => Some(Value::Number(self.height)),
+            3 => Some(Value::Number(self.radius)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let corner = inputs[0].as_point()?;
+        let width = length("Width", &inputs[1])?;
+        let height = length("Height", &inputs[2])?;
+        // Radii larger than half of the shorter side are cut down by kurbo
+        let radius = length("Radius", &inputs[3])?;
+        let rect = kurbo::RoundedRect::from_origin_size(corner, (width, height), radius);
+        Ok(vec![Value::Shape(Shapes::RoundedRect(rect))])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Ellipse from its center, two radii and a rotation in radians
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct EllipseNode {
+    center: Point,
+    radius_x: f64,
+    radius_y: f64,
+    rotation: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for EllipseNode {
+    fn default() -> Self {
+        Self {
+            center: Point::ZERO,
+            radius_x: 20.0,
+            radius_y: 10.0,
+            rotation: 0.0,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for EllipseNode {}
+impl super::NodeInfo for EllipseNode {
+    fn inputs() -> usize {
+        4
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Ellipse".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Point,
+            _ => ValueType::Number,
+        }
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
+}
+impl super::NodeDowncast for EllipseNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Ellipse(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Ellipse(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for EllipseNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_point_input("Center", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).center
+            }),
+            1 => {
+                super::show_number_input("Radius X", pin, ui, scale, snarl, results, |id, snarl| {
+                    &mut super::get_node_mut::<Self>(snarl, id.node).radius_x
+                })
+            }
+            2 => {
+                super::show_number_input("Radius Y", pin, ui, scale, snarl, results, |id, snarl| {
+                    &mut super::get_node_mut::<Self>(snarl, id.node).radius_y
+                })
+            }
+            3 => {
+                super::show_number_input("Rotation", pin, ui, scale, snarl, results, |id, snarl| {
+                    &mut super::get_node_mut::<Self>(snarl, id.node).rotation
+                })
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for EllipseNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_shape_output("Ellipse", ui)
+    }
+}
+impl super::SolveNode for EllipseNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(self.center)),
+            1 => Some(Value::Number(self.radius_x)),
+            2 => Some(Value::Number(self.radius_y)),
+            3 => Some(Value::Number(self.rotation)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let center = inputs[0].as_point()?;
+        let radius_x = length("Radius X", &inputs[1])?;
+        let radius_y = length("Radius Y", &inputs[2])?;
+        let rotation = inputs[3].as_number()?;
+        let ellipse = kurbo::Ellipse::new(center, (radius_x, radius_y), rotation);
+        Ok(vec![Value::Shape(Shapes::Ellipse(ellipse))])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Straight line between two points
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct LineNode {
+    start: Point,
+    end: Point,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for LineNode {
+    fn default() -> Self {
+        Self {
+            start: Point::ZERO,
+            end: Point::new(20.0, 20.0),
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for LineNode {}
+impl super::NodeInfo for LineNode {
+    fn inputs() -> usize {
+        2
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Line".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Point
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
+}
+impl super::NodeDowncast for LineNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Line(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Line(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for LineNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_point_input("Start", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).start
+            }),
+            1 => super::show_point_input("End", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).end
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for LineNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_shape_output("Line", ui)
+    }
+}
+impl super::SolveNode for LineNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(self.start)),
+            1 => Some(Value::Point(self.end)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let line = kurbo::Line::new(inputs[0].as_point()?, inputs[1].as_point()?);
+        Ok(vec![Value::Shape(Shapes::Line(line))])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Part of a circle, from a start angle sweeping by another angle,
+/// both in radians and clockwise on the canvas
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ArcNode {
+    center: Point,
+    radius: f64,
+    start: f64,
+    sweep: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for ArcNode {
+    fn default() -> Self {
+        Self {
+            center: Point::ZERO,
+            radius: 20.0,
+            start: 0.0,
+            sweep: std::f64::consts::FRAC_PI_2,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for ArcNode {}
+impl super::NodeInfo for ArcNode {
+    fn inputs() -> usize {
+        4
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Arc".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Point,
+            _ => ValueType::Number,
+        }
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
+}
+impl super::NodeDowncast for ArcNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Arc(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Arc(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for ArcNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_point_input("Center", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).center
+            }),
+            1 => super::show_number_input("Radius", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).radius
+            }),
+            2 => super::show_number_input("Start", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).start
+            }),
+            3 => super::show_number_input("Sweep", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).sweep
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for ArcNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_shape_output("Arc", ui)
+    }
+}
+impl super::SolveNode for ArcNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(self.center)),
+            1 => Some(Value::Number(self.radius)),
+            2 => Some(Value::Number(self.start)),
+            3 => Some(Value::Number(self.sweep)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let center = inputs[0].as_point()?;
+        let radius = length("Radius", &inputs[1])?;
+        let arc = kurbo::Arc {
+            center,
+            radii: kurbo::Vec2::new(radius, radius),
+            start_angle: inputs[2].as_number()?,
+            sweep_angle: inputs[3].as_number()?,
+            x_rotation: 0.0,
+        };
+        Ok(vec![Value::Shape(Shapes::Arc(arc))])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Straight segments through a list of points
+fn polyline(points: &[Point], closed: bool) -> Result<BezPath, EvalError> {
+    let [first, rest @ ..] = points else {
+        return Err(EvalError::Domain(
+            "A polyline needs at least two points".to_string(),
+        ));
+    };
+    if rest.is_empty() {
+        return Err(EvalError::Domain(
+            "A polyline needs at least two points".to_string(),
+        ));
+    }
+    let mut path = BezPath::new();
+    path.move_to(*first);
+    for point in rest {
+        path.line_to(*point);
+    }
+    if closed {
+        path.close_path();
+    }
+    Ok(path)
+}
+
+/// Connects a list of points by straight segments, closing the
+/// outline for polygons
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug)]
+pub struct PolylineNode {
+    #[serde(default)]
+    pub closed: bool,
+}
+
+impl super::Node for PolylineNode {}
+impl super::NodeInfo for PolylineNode {
+    fn inputs() -> usize {
+        1
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Polyline".to_string()
+    }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Point
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
+}
+impl super::NodeDowncast for PolylineNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Polyline(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Polyline(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for PolylineNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        super::show_data_input("Points", pin, ui, scale, results).with_fill(crate::POINT_COLOR)
+    }
+}
+impl super::OutputNode<super::Nodes> for PolylineNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        let node = super::get_node_mut::<Self>(snarl, pin.id.node);
+        ui.checkbox(&mut node.closed, "Closed");
+        show_shape_output("Path", ui)
+    }
+}
+impl super::SolveNode for PolylineNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        None
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let points = inputs[0].to_points()?;
+        let path = polyline(&points, self.closed)?;
+        Ok(vec![Value::Shape(Shapes::Path(path))])
+    }
+
+    fn input_access(&self, _index: usize) -> Access {
+        Access::List
+    }
+}
+
+/// Regular polygon with its corners on a circle, the first one
+/// pointing right of the center
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct PolygonNode {
+    center: Point,
+    radius: f64,
+    sides: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for PolygonNode {
+    fn default() -> Self {
+        Self {
+            center: Point::ZERO,
+            radius: 20.0,
+            sides: 6.0,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl super::Node for PolygonNode {}
+impl super::NodeInfo for PolygonNode {
+    fn inputs() -> usize {
+        3
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Polygon".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Point,
+            _ => ValueType::Number,
+        }
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
+}
+impl super::NodeDowncast for PolygonNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Polygon(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Polygon(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for PolygonNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_point_input("Center", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).center
+            }),
+            1 => super::show_number_input("Radius", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).radius
+            }),
+            2 => super::show_number_input("Sides", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).sides
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for PolygonNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_shape_output("Path", ui)
+    }
+}
+impl super::SolveNode for PolygonNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(self.center)),
+            1 => Some(Value::Number(self.radius)),
+            2 => Some(Value::Number(self.sides)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let center = inputs[0].as_point()?;
+        let radius = length("Radius", &inputs[1])?;
+        let sides = inputs[2].as_count()?;
+        if sides < 3 {
+            return Err(EvalError::Domain(format!(
+                "A polygon needs at least 3 sides, but has {sides}"
+            )));
+        }
+        let corners = (0..sides)
+            .map(|index| {
+                let angle = std::f64::consts::TAU * index as f64 / sides as f64;
+                center + kurbo::Vec2::from_angle(angle) * radius
+            })
+            .collect::<Vec<_>>();
+        let path = polyline(&corners, true)?;
+        Ok(vec![Value::Shape(Shapes::Path(path))])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
@@ -2,13 +2,20 @@ use egui_snarl::ui::PinInfo;
 
 use crate::nodes::NodeDowncast;
 
-use super::{InputNode, NodeInfo, Nodes};
+use crate::{
+    solver::{EvalError, ListMatching, Results},
+    value::{Value, ValueType},
+};
+
+use super::{InputNode, NodeInfo, Nodes, SolveNode};
 
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct RangeNode {
     start: f64,
     step: f64,
     count: usize,
+    #[serde(default)]
+    matching: ListMatching,
 }
 
 impl RangeNode {
@@ -20,10 +27,7 @@ impl RangeNode {
 /// inside of the snarl, so if we bind it to a variable
 /// we will have to separate mutable references into
 /// the snarls inner storage, which will make borowck cry
-fn get_node_mut<'a>(
-    snarl: &'a mut egui_snarl::Snarl<Nodes>,
-    id: egui_snarl::InPinId,
-) -> &'a mut RangeNode {
+fn get_node_mut(snarl: &mut egui_snarl::Snarl<Nodes>, id: egui_snarl::InPinId) -> &mut RangeNode {
     RangeNode::try_downcast_mut(&mut snarl[id.node]).expect("Is ok")
 }
 
@@ -51,6 +55,7 @@ impl Default for RangeNode {
             start: 0.0,
             step: 1.0,
             count: 10,
+            matching: ListMatching::default(),
         }
     }
 }
@@ -67,6 +72,14 @@ impl NodeInfo for RangeNode {
     fn title() -> String {
         "Range".to_string()
     }
+
+    fn input_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Number
+    }
 }
 
 fn show_start_input(
@@ -74,8 +87,9 @@ fn show_start_input(
     ui: &mut egui::Ui,
     scale: f32,
     snarl: &mut egui_snarl::Snarl<Nodes>,
+    results: &Results,
 ) -> PinInfo {
-    super::show_number_input("Start", pin, ui, scale, snarl, |id, snarl| {
+    super::show_number_input("Start", pin, ui, scale, snarl, results, |id, snarl| {
         &mut get_node_mut(snarl, id).start
     })
 }
@@ -85,8 +99,9 @@ fn show_step_input(
     ui: &mut egui::Ui,
     scale: f32,
     snarl: &mut egui_snarl::Snarl<Nodes>,
+    results: &Results,
 ) -> PinInfo {
-    super::show_number_input("Step", pin, ui, scale, snarl, |id, snarl| {
+    super::show_number_input("Step", pin, ui, scale, snarl, results, |id, snarl| {
         &mut get_node_mut(snarl, id).step
     })
 }
@@ -94,29 +109,25 @@ fn show_step_input(
 fn show_count_input(
     pin: &egui_snarl::InPin,
     ui: &mut egui::Ui,
-    scale: f32,
+    _scale: f32,
     snarl: &mut egui_snarl::Snarl<Nodes>,
+    results: &Results,
 ) -> PinInfo {
-    let info: PinInfo;
     ui.label("Count");
-    match &*pin.remotes {
-        [] => {
-            ui.add(egui::DragValue::new(&mut get_node_mut(snarl, pin.id).count));
-            info = PinInfo::square().with_fill(crate::UNCONNECTED_COLOR);
+    if pin.remotes.is_empty() {
+        ui.add(egui::DragValue::new(&mut get_node_mut(snarl, pin.id).count));
+        return PinInfo::square().with_fill(crate::UNCONNECTED_COLOR);
+    }
+    match results.input(pin) {
+        Some(value) => {
+            ui.label(value.to_string());
+            PinInfo::square().with_fill(crate::NUMBER_COLOR)
         }
-        [remote] => {
-            if let Some(value) = snarl[remote.node].try_get_float() {
-                get_node_mut(snarl, pin.id).count = value as usize;
-                ui.label(super::format_float(value));
-                info = PinInfo::square().with_fill(crate::NUMBER_COLOR);
-            } else {
-                ui.add(egui::DragValue::new(&mut get_node_mut(snarl, pin.id).count));
-                info = PinInfo::square().with_fill(crate::UNCONNECTED_COLOR);
-            }
+        None => {
+            ui.label("None");
+            PinInfo::square().with_fill(crate::UNCONNECTED_COLOR)
         }
-        _ => unreachable!(),
     }
-    info
 }
 
 impl InputNode<Nodes> for RangeNode {
@@ -125,6 +136,7 @@ impl InputNode<Nodes> for RangeNode {
         ui: &mut egui::Ui,
         scale: f32,
         snarl: &mut egui_snarl::Snarl<Nodes>,
+        results: &Results,
     ) -> PinInfo {
         ui.set_width(120.0 * scale);
         ui.set_height(16.0 * scale);
@@ -134,13 +146,13 @@ impl InputNode<Nodes> for RangeNode {
                 ui.add_space(20.0 * scale);
                 match pin.id.input {
                     0 => {
-                        show_start_input(pin, ui, scale, snarl);
+                        show_start_input(pin, ui, scale, snarl, results);
                     }
                     1 => {
-                        show_step_input(pin, ui, scale, snarl);
+                        show_step_input(pin, ui, scale, snarl, results);
                     }
                     2 => {
-                        show_count_input(pin, ui, scale, snarl);
+                        show_count_input(pin, ui, scale, snarl, results);
                     }
                     _ => unreachable!(),
                 };
@@ -152,11 +164,41 @@ impl InputNode<Nodes> for RangeNode {
 
 impl super::OutputNode<super::Nodes> for RangeNode {
     fn show_output(
-        pin: &egui_snarl::OutPin,
-        ui: &mut egui::Ui,
-        scale: f32,
-        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _pin: &egui_snarl::OutPin,
+        _ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
     ) -> PinInfo {
         PinInfo::square().with_fill(crate::NUMBER_COLOR)
     }
 }
+
+impl SolveNode for RangeNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Number(self.start)),
+            1 => Some(Value::Number(self.step)),
+            2 => Some(Value::Number(self.count as f64)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let start = inputs[0].as_number()?;
+        let step = inputs[1].as_number()?;
+        let count = inputs[2].as_count()?;
+        let numbers = (0..count)
+            .map(|i| Value::Number(start + step * i as f64))
+            .collect();
+        Ok(vec![Value::List(numbers)])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
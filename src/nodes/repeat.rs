@@ -1,11 +1,20 @@
 use egui_snarl::ui::PinInfo;
+use piet::kurbo::{Affine, Point};
 
-use crate::shapes::Shapes;
+use crate::{
+    shapes::Shapes,
+    solver::{EvalError, ListMatching, Results},
+    value::{Value, ValueType},
+};
 
+/// The same shape a number of times, without moving the copies.
+/// See the array nodes for copies which are moved apart
 #[derive(serde::Serialize, serde::Deserialize, Default)]
 pub struct RepeatShapeNode {
     shape: Shapes,
     count: f64,
+    #[serde(default)]
+    matching: ListMatching,
 }
 
 impl super::Node for RepeatShapeNode {}
@@ -21,10 +30,24 @@ impl super::NodeInfo for RepeatShapeNode {
     fn title() -> String {
         "RepeatShape".to_string()
     }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Shape,
+            _ => ValueType::Number,
+        }
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
 }
 impl super::NodeDowncast for RepeatShapeNode {
     fn try_downcast(from: &super::Nodes) -> Option<&Self> {
-        todo!()
+        match from {
+            super::Nodes::RepeatShape(node) => Some(node),
+            _ => None,
+        }
     }
 
     fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
@@ -40,12 +63,11 @@ impl super::InputNode<super::Nodes> for RepeatShapeNode {
         ui: &mut egui::Ui,
         scale: f32,
         snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
     ) -> egui_snarl::ui::PinInfo {
         match pin.id.input {
-            0 => super::show_shape_input("Shape", pin, ui, scale, snarl, |id, snarl| {
-                &mut super::get_node_mut::<Self>(snarl, id.node).shape
-            }),
-            1 => super::show_number_input("Count", pin, ui, scale, snarl, |id, snarl| {
+            0 => super::show_shape_input("Shape", pin, ui, scale, results),
+            1 => super::show_number_input("Count", pin, ui, scale, snarl, results, |id, snarl| {
                 &mut super::get_node_mut::<Self>(snarl, id.node).count
             }),
             _ => unreachable!(),
@@ -54,21 +76,503 @@ impl super::InputNode<super::Nodes> for RepeatShapeNode {
 }
 impl super::OutputNode<super::Nodes> for RepeatShapeNode {
     fn show_output(
-        pin: &egui_snarl::OutPin,
+        _pin: &egui_snarl::OutPin,
         ui: &mut egui::Ui,
-        scale: f32,
-        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
     ) -> egui_snarl::ui::PinInfo {
         ui.label("Shapes");
         PinInfo::triangle().with_fill(crate::SHAPE_COLOR)
     }
 }
-impl super::EmitterNode<Shapes> for RepeatShapeNode {
-    fn value_out(&self) -> Shapes {
-        self.shape.clone()
+
+impl super::SolveNode for RepeatShapeNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Shape(self.shape.clone())),
+            1 => Some(Value::Number(self.count)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let shape = inputs[0].as_shape()?;
+        let count = inputs[1].as_count()?;
+        let shapes = (0..count).map(|_| Value::Shape(shape.clone())).collect();
+        Ok(vec![Value::List(shapes)])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Puts out the copies of the geometry, one for each transform,
+/// together with the transforms themselves. Without geometry,
+/// the copies are of the origin
+fn array(geometry: &Value, transforms: Vec<Affine>) -> Result<Vec<Value>, EvalError> {
+    let copies = transforms
+        .iter()
+        .map(|affine| geometry.transform(*affine))
+        .collect::<Result<_, _>>()?;
+    let transforms = transforms.into_iter().map(Value::Transform).collect();
+    Ok(vec![Value::List(copies), Value::List(transforms)])
+}
+
+fn show_array_output(pin: &egui_snarl::OutPin, ui: &mut egui::Ui) -> PinInfo {
+    match pin.id.output {
+        0 => {
+            ui.label("Copies");
+            PinInfo::circle()
+        }
+        1 => {
+            ui.label("Transforms");
+            PinInfo::circle().with_fill(crate::TRANSFORM_COLOR)
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Copies of geometry rotated about a center, spread evenly over
+/// an angle in radians. A full turn doesn't repeat the first copy
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct PolarArrayNode {
+    count: f64,
+    angle: f64,
+    center: Point,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for PolarArrayNode {
+    fn default() -> Self {
+        Self {
+            count: 6.0,
+            angle: std::f64::consts::TAU,
+            center: Point::ZERO,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl PolarArrayNode {
+    fn transforms(inputs: &[Value]) -> Result<Vec<Affine>, EvalError> {
+        let count = inputs[1].as_count()?;
+        let angle = inputs[2].as_number()?;
+        let center = inputs[3].as_point()?;
+        let full_turn = (angle.abs() - std::f64::consts::TAU).abs() < 1e-9;
+        let steps = if full_turn || count < 2 {
+            count
+        } else {
+            count - 1
+        };
+        Ok((0..count)
+            .map(|index| Affine::rotate_about(angle * index as f64 / steps as f64, center))
+            .collect())
+    }
+}
+
+impl super::Node for PolarArrayNode {}
+impl super::NodeInfo for PolarArrayNode {
+    fn inputs() -> usize {
+        4
+    }
+
+    fn outputs() -> usize {
+        2
+    }
+
+    fn title() -> String {
+        "Polar Array".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Any,
+            1 => ValueType::Number,
+            2 => ValueType::Number,
+            3 => ValueType::Point,
+            _ => unreachable!(),
+        }
+    }
+
+    fn output_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Any,
+            _ => ValueType::Transform,
+        }
+    }
+}
+impl super::NodeDowncast for PolarArrayNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::PolarArray(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::PolarArray(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for PolarArrayNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_data_input("Geometry", pin, ui, scale, results),
+            1 => super::show_number_input("Count", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).count
+            }),
+            2 => super::show_number_input("Angle", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).angle
+            }),
+            3 => super::show_point_input("Center", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).center
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for PolarArrayNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_array_output(pin, ui)
+    }
+}
+impl super::SolveNode for PolarArrayNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(Point::ZERO)),
+            1 => Some(Value::Number(self.count)),
+            2 => Some(Value::Number(self.angle)),
+            3 => Some(Value::Point(self.center)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        array(&inputs[0], Self::transforms(inputs)?)
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Copies of geometry in a row, each moved further along a direction
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct LinearArrayNode {
+    count: f64,
+    direction: Point,
+    spacing: f64,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for LinearArrayNode {
+    fn default() -> Self {
+        Self {
+            count: 5.0,
+            direction: Point::new(1.0, 0.0),
+            spacing: 20.0,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl LinearArrayNode {
+    fn transforms(inputs: &[Value]) -> Result<Vec<Affine>, EvalError> {
+        let count = inputs[1].as_count()?;
+        let direction = inputs[2].as_point()?.to_vec2();
+        let spacing = inputs[3].as_number()?;
+        let length = direction.hypot();
+        if length == 0.0 {
+            return Err(EvalError::Domain(
+                "The direction of the array can't be zero".to_string(),
+            ));
+        }
+        let step = direction / length * spacing;
+        Ok((0..count)
+            .map(|index| Affine::translate(step * index as f64))
+            .collect())
+    }
+}
+
+impl super::Node for LinearArrayNode {}
+impl super::NodeInfo for LinearArrayNode {
+    fn inputs() -> usize {
+        4
+    }
+
+    fn outputs() -> usize {
+        2
+    }
+
+    fn title() -> String {
+        "Linear Array".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Any,
+            1 => ValueType::Number,
+            2 => ValueType::Point,
+            3 => ValueType::Number,
+            _ => unreachable!(),
+        }
+    }
+
+    fn output_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Any,
+            _ => ValueType::Transform,
+        }
+    }
+}
+impl super::NodeDowncast for LinearArrayNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::LinearArray(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::LinearArray(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for LinearArrayNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_data_input("Geometry", pin, ui, scale, results),
+            1 => super::show_number_input("Count", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).count
+            }),
+            2 => {
+                super::show_point_input("Direction", pin, ui, scale, snarl, results, |id, snarl| {
+                    &mut super::get_node_mut::<Self>(snarl, id.node).direction
+                })
+            }
+            3 => {
+                super::show_number_input("Spacing", pin, ui, scale, snarl, results, |id, snarl| {
+                    &mut super::get_node_mut::<Self>(snarl, id.node).spacing
+                })
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for LinearArrayNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_array_output(pin, ui)
+    }
+}
+impl super::SolveNode for LinearArrayNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(Point::ZERO)),
+            1 => Some(Value::Number(self.count)),
+            2 => Some(Value::Point(self.direction)),
+            3 => Some(Value::Number(self.spacing)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        array(&inputs[0], Self::transforms(inputs)?)
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Copies of geometry in rows and columns, row by row. The spacing
+/// can differ along x and y, a single number spaces both alike
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct GridArrayNode {
+    rows: f64,
+    columns: f64,
+    spacing: Point,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for GridArrayNode {
+    fn default() -> Self {
+        Self {
+            rows: 3.0,
+            columns: 3.0,
+            spacing: Point::new(20.0, 20.0),
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl GridArrayNode {
+    fn transforms(inputs: &[Value]) -> Result<Vec<Affine>, EvalError> {
+        let rows = inputs[1].as_count()?;
+        let columns = inputs[2].as_count()?;
+        if rows.saturating_mul(columns) > Value::MAX_COUNT {
+            return Err(EvalError::Domain(format!(
+                "A grid can have at most {} cells, but has {rows} rows of {columns}",
+                Value::MAX_COUNT
+            )));
+        }
+        let spacing = inputs[3].as_point()?;
+        Ok((0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| {
+                    Affine::translate((column as f64 * spacing.x, row as f64 * spacing.y))
+                })
+            })
+            .collect())
+    }
+}
+
+impl super::Node for GridArrayNode {}
+impl super::NodeInfo for GridArrayNode {
+    fn inputs() -> usize {
+        4
+    }
+
+    fn outputs() -> usize {
+        2
+    }
+
+    fn title() -> String {
+        "Grid Array".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Any,
+            1 => ValueType::Number,
+            2 => ValueType::Number,
+            3 => ValueType::Point,
+            _ => unreachable!(),
+        }
+    }
+
+    fn output_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Any,
+            _ => ValueType::Transform,
+        }
+    }
+}
+impl super::NodeDowncast for GridArrayNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::GridArray(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::GridArray(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for GridArrayNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_data_input("Geometry", pin, ui, scale, results),
+            1 => super::show_number_input("Rows", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).rows
+            }),
+            2 => {
+                super::show_number_input("Columns", pin, ui, scale, snarl, results, |id, snarl| {
+                    &mut super::get_node_mut::<Self>(snarl, id.node).columns
+                })
+            }
+            3 => super::show_point_input("Spacing", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).spacing
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for GridArrayNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_array_output(pin, ui)
+    }
+}
+impl super::SolveNode for GridArrayNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(Point::ZERO)),
+            1 => Some(Value::Number(self.rows)),
+            2 => Some(Value::Number(self.columns)),
+            3 => Some(Value::Point(self.spacing)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        array(&inputs[0], Self::transforms(inputs)?)
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
     }
 
-    fn values_out(&self) -> impl Iterator<Item = Shapes> + '_ {
-        (0..self.count as usize).map(|_| self.shape.clone())
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
     }
 }
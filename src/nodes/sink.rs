@@ -1,7 +1,12 @@
 use egui::Ui;
-use egui_snarl::{ui::PinInfo, InPin, Snarl};
+use egui_snarl::{ui::PinInfo, InPin};
 
-use super::{Node, NodeInfo, Nodes};
+use crate::{
+    solver::{Access, EvalError, Results},
+    value::Value,
+};
+
+use super::{Node, NodeInfo, SolveNode};
 
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct SinkNode;
@@ -21,44 +26,71 @@ impl NodeInfo for SinkNode {
         "Sink".to_string()
     }
 }
-pub fn format_float(value: f64) -> String {
-    let value = (value * 1000.0).round() / 1000.0;
-    format!("{}", value)
-}
-pub fn show_input(pin: &InPin, ui: &mut Ui, scale: f32, snarl: &Snarl<Nodes>) -> PinInfo {
-    // TODO: Probably there is more to display than numbers
-    match &*pin.remotes {
-        [] => {
+pub fn show_input(pin: &InPin, ui: &mut Ui, scale: f32, results: &Results) -> PinInfo {
+    match results.input(pin) {
+        None => {
             ui.label("None");
             PinInfo::circle().with_fill(crate::UNCONNECTED_COLOR)
         }
-        [remote] => match &snarl[remote.node] {
-            Nodes::ConstantValueNode(value) => {
-                ui.label(format_float(value.number_out()));
-                PinInfo::square().with_fill(crate::NUMBER_COLOR)
-            }
-            Nodes::Range(node) => {
-                egui::ScrollArea::vertical()
-                    .max_height(30.0 * scale)
-                    .show(ui, |ui| {
-                        ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
-                            for number in node.get_numbers() {
-                                ui.label(super::format_float(number));
+        Some(Value::List(values)) => {
+            egui::ScrollArea::vertical()
+                .max_height(30.0 * scale)
+                .show(ui, |ui| {
+                    ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
+                        for value in values {
+                            ui.label(value.to_string());
+                        }
+                    })
+                });
+            values
+                .first()
+                .map(pin_info)
+                .unwrap_or_else(|| PinInfo::circle().with_fill(crate::UNCONNECTED_COLOR))
+        }
+        Some(Value::Tree(tree)) => {
+            egui::ScrollArea::vertical()
+                .max_height(30.0 * scale)
+                .show(ui, |ui| {
+                    ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
+                        for branch in tree.branches() {
+                            ui.label(branch.path.to_string());
+                            for value in &branch.items {
+                                ui.label(format!("  {value}"));
                             }
-                        })
-                    });
-                PinInfo::square().with_fill(crate::NUMBER_COLOR)
-            }
-            Nodes::Point(node) => {
-                ui.label(format!("{:?}", node.point_out()));
-                PinInfo::circle().with_fill(crate::POINT_COLOR)
-            }
-            Nodes::Circle(node) => {
-                ui.label(format!("{:?}", node.circle_out()));
-                PinInfo::triangle().with_fill(crate::SHAPE_COLOR)
-            }
-            _ => unreachable!(),
-        },
-        _ => unreachable!(),
+                        }
+                    })
+                });
+            tree.items()
+                .next()
+                .map(pin_info)
+                .unwrap_or_else(|| PinInfo::circle().with_fill(crate::UNCONNECTED_COLOR))
+        }
+        Some(value) => {
+            ui.label(value.to_string());
+            pin_info(value)
+        }
+    }
+}
+
+fn pin_info(value: &Value) -> PinInfo {
+    match value {
+        Value::Number(_) => PinInfo::square().with_fill(crate::NUMBER_COLOR),
+        Value::Point(_) => PinInfo::circle().with_fill(crate::POINT_COLOR),
+        Value::Shape(_) | Value::Curve(_) => PinInfo::triangle().with_fill(crate::SHAPE_COLOR),
+        _ => PinInfo::circle(),
+    }
+}
+
+impl SolveNode for SinkNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        None
+    }
+
+    fn solve(&self, _inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        Ok(Vec::new())
+    }
+
+    fn input_access(&self, _index: usize) -> Access {
+        Access::Tree
     }
 }
@@ -0,0 +1,205 @@
+use egui_snarl::ui::PinInfo;
+
+use crate::{
+    color::Color,
+    solver::{EvalError, ListMatching, Results},
+    style::{LineCap, LineJoin, Style},
+    value::{Value, ValueType},
+};
+
+/// Paints shapes with a style of their own. The line cap
+/// and join are settings of the node, as they have no pins
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+#[serde(default)]
+pub struct StyleNode {
+    fill: Color,
+    stroke: Color,
+    width: f64,
+    opacity: f64,
+    /// Dash and gap lengths separated by spaces or commas
+    dash: String,
+    cap: LineCap,
+    join: LineJoin,
+    matching: ListMatching,
+}
+
+impl Default for StyleNode {
+    fn default() -> Self {
+        let style = Style::default();
+        Self {
+            // Transparent, as shapes aren't filled by default
+            fill: Color::rgba(0.0, 0.0, 0.0, 0.0),
+            stroke: style.stroke.unwrap_or_default(),
+            width: style.width,
+            opacity: style.opacity,
+            dash: String::new(),
+            cap: style.cap,
+            join: style.join,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl StyleNode {
+    pub fn show_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Cap");
+            for cap in LineCap::ALL {
+                ui.radio_value(&mut self.cap, cap, cap.name());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Join");
+            for join in LineJoin::ALL {
+                ui.radio_value(&mut self.join, join, join.name());
+            }
+        });
+    }
+}
+
+/// Reads dash patterns like `4 2` or `4, 2, 1, 2`
+fn parse_dash(text: &str) -> Result<Vec<f64>, EvalError> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.parse::<f64>() {
+            Ok(length) if length >= 0.0 => Ok(length),
+            _ => Err(EvalError::Domain(format!(
+                "Dash lengths have to be positive numbers, but '{part}' isn't"
+            ))),
+        })
+        .collect()
+}
+
+impl super::Node for StyleNode {}
+impl super::NodeInfo for StyleNode {
+    fn inputs() -> usize {
+        6
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Style".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Shape,
+            1 | 2 => ValueType::Color,
+            3 | 4 => ValueType::Number,
+            _ => ValueType::Text,
+        }
+    }
+
+    fn output_type(_index: usize) -> ValueType {
+        ValueType::Shape
+    }
+}
+impl super::NodeDowncast for StyleNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Style(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Style(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for StyleNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> egui_snarl::ui::PinInfo {
+        match pin.id.input {
+            0 => super::show_shape_input("Shape", pin, ui, scale, results),
+            1 => super::show_color_input("Fill", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).fill
+            }),
+            2 => super::show_color_input("Stroke", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).stroke
+            }),
+            3 => super::show_number_input("Width", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).width
+            }),
+            4 => {
+                super::show_number_input("Opacity", pin, ui, scale, snarl, results, |id, snarl| {
+                    &mut super::get_node_mut::<Self>(snarl, id.node).opacity
+                })
+            }
+            5 => super::show_text_input("Dash", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).dash
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl super::OutputNode<super::Nodes> for StyleNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> egui_snarl::ui::PinInfo {
+        ui.label("Shape");
+        PinInfo::triangle().with_fill(crate::SHAPE_COLOR)
+    }
+}
+
+impl super::SolveNode for StyleNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            1 => Some(Value::Color(self.fill)),
+            2 => Some(Value::Color(self.stroke)),
+            3 => Some(Value::Number(self.width)),
+            4 => Some(Value::Number(self.opacity)),
+            5 => Some(Value::Text(self.dash.clone())),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let shape = inputs[0].as_shape()?;
+        let width = inputs[3].as_number()?;
+        if width < 0.0 {
+            return Err(EvalError::Domain(format!(
+                "Width can't be negative, but is {width}"
+            )));
+        }
+        let opacity = inputs[4].as_number()?;
+        if !(0.0..=1.0).contains(&opacity) {
+            return Err(EvalError::Domain(format!(
+                "Opacity has to be between 0 and 1, but is {opacity}"
+            )));
+        }
+        let style = Style {
+            fill: Some(inputs[1].as_color()?),
+            stroke: Some(inputs[2].as_color()?),
+            width,
+            dash: parse_dash(&inputs[5].as_text()?)?,
+            cap: self.cap,
+            join: self.join,
+            opacity,
+        };
+        Ok(vec![Value::Shape(shape.with_style(style))])
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
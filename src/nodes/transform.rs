@@ -0,0 +1,657 @@
+use egui_snarl::ui::PinInfo;
+use piet::kurbo::{Affine, Point};
+
+use crate::{
+    solver::{Access, EvalError, ListMatching, Results},
+    value::{Value, ValueType},
+};
+
+fn show_geometry_input(
+    pin: &egui_snarl::InPin,
+    ui: &mut egui::Ui,
+    scale: f32,
+    results: &Results,
+) -> PinInfo {
+    super::show_data_input("Geometry", pin, ui, scale, results)
+}
+
+/// Every transform node puts out the moved geometry and the transform
+/// itself, which a Compose node can chain with others
+fn show_transform_output(pin: &egui_snarl::OutPin, ui: &mut egui::Ui) -> PinInfo {
+    match pin.id.output {
+        0 => {
+            ui.label("Geometry");
+            PinInfo::circle()
+        }
+        1 => {
+            ui.label("Transform");
+            PinInfo::circle().with_fill(crate::TRANSFORM_COLOR)
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn transform_output_type(index: usize) -> ValueType {
+    match index {
+        0 => ValueType::Any,
+        _ => ValueType::Transform,
+    }
+}
+
+/// Applies the transform to the geometry, which is the
+/// origin if nothing is wired in
+fn transformed(geometry: &Value, affine: Affine) -> Result<Vec<Value>, EvalError> {
+    Ok(vec![geometry.transform(affine)?, Value::Transform(affine)])
+}
+
+/// Moves geometry by an offset
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct TranslateNode {
+    offset: Point,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for TranslateNode {
+    fn default() -> Self {
+        Self {
+            offset: Point::new(10.0, 0.0),
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl TranslateNode {
+    fn affine(inputs: &[Value]) -> Result<Affine, EvalError> {
+        let offset = inputs[1].as_point()?.to_vec2();
+        Ok(Affine::translate(offset))
+    }
+}
+
+impl super::Node for TranslateNode {}
+impl super::NodeInfo for TranslateNode {
+    fn inputs() -> usize {
+        2
+    }
+
+    fn outputs() -> usize {
+        2
+    }
+
+    fn title() -> String {
+        "Translate".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Any,
+            1 => ValueType::Point,
+            _ => unreachable!(),
+        }
+    }
+
+    fn output_type(index: usize) -> ValueType {
+        transform_output_type(index)
+    }
+}
+impl super::NodeDowncast for TranslateNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Translate(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Translate(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for TranslateNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => show_geometry_input(pin, ui, scale, results),
+            1 => super::show_point_input("Offset", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).offset
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for TranslateNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_transform_output(pin, ui)
+    }
+}
+impl super::SolveNode for TranslateNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(Point::ZERO)),
+            1 => Some(Value::Point(self.offset)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        transformed(&inputs[0], Self::affine(inputs)?)
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Rotates geometry about a center by an angle in radians,
+/// clockwise on the canvas
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RotateNode {
+    angle: f64,
+    center: Point,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for RotateNode {
+    fn default() -> Self {
+        Self {
+            angle: std::f64::consts::FRAC_PI_4,
+            center: Point::ZERO,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl RotateNode {
+    fn affine(inputs: &[Value]) -> Result<Affine, EvalError> {
+        let angle = inputs[1].as_number()?;
+        let center = inputs[2].as_point()?;
+        Ok(Affine::rotate_about(angle, center))
+    }
+}
+
+impl super::Node for RotateNode {}
+impl super::NodeInfo for RotateNode {
+    fn inputs() -> usize {
+        3
+    }
+
+    fn outputs() -> usize {
+        2
+    }
+
+    fn title() -> String {
+        "Rotate".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Any,
+            1 => ValueType::Number,
+            2 => ValueType::Point,
+            _ => unreachable!(),
+        }
+    }
+
+    fn output_type(index: usize) -> ValueType {
+        transform_output_type(index)
+    }
+}
+impl super::NodeDowncast for RotateNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Rotate(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Rotate(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for RotateNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => show_geometry_input(pin, ui, scale, results),
+            1 => super::show_number_input("Angle", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).angle
+            }),
+            2 => super::show_point_input("Center", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).center
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for RotateNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_transform_output(pin, ui)
+    }
+}
+impl super::SolveNode for RotateNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(Point::ZERO)),
+            1 => Some(Value::Number(self.angle)),
+            2 => Some(Value::Point(self.center)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        transformed(&inputs[0], Self::affine(inputs)?)
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Scales geometry away from a center, by separate factors along x
+/// and y. A single number scales both alike
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ScaleNode {
+    factor: Point,
+    center: Point,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for ScaleNode {
+    fn default() -> Self {
+        Self {
+            factor: Point::new(2.0, 2.0),
+            center: Point::ZERO,
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl ScaleNode {
+    fn affine(inputs: &[Value]) -> Result<Affine, EvalError> {
+        let factor = inputs[1].as_point()?;
+        let center = inputs[2].as_point()?.to_vec2();
+        Ok(Affine::translate(center)
+            * Affine::scale_non_uniform(factor.x, factor.y)
+            * Affine::translate(-center))
+    }
+}
+
+impl super::Node for ScaleNode {}
+impl super::NodeInfo for ScaleNode {
+    fn inputs() -> usize {
+        3
+    }
+
+    fn outputs() -> usize {
+        2
+    }
+
+    fn title() -> String {
+        "Scale".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Any,
+            1 => ValueType::Point,
+            2 => ValueType::Point,
+            _ => unreachable!(),
+        }
+    }
+
+    fn output_type(index: usize) -> ValueType {
+        transform_output_type(index)
+    }
+}
+impl super::NodeDowncast for ScaleNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Scale(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Scale(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for ScaleNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => show_geometry_input(pin, ui, scale, results),
+            1 => super::show_point_input("Factor", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).factor
+            }),
+            2 => super::show_point_input("Center", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).center
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for ScaleNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_transform_output(pin, ui)
+    }
+}
+impl super::SolveNode for ScaleNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(Point::ZERO)),
+            1 => Some(Value::Point(self.factor)),
+            2 => Some(Value::Point(self.center)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        transformed(&inputs[0], Self::affine(inputs)?)
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Mirrors geometry across the line through a point along a direction
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct MirrorNode {
+    origin: Point,
+    direction: Point,
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl Default for MirrorNode {
+    fn default() -> Self {
+        Self {
+            origin: Point::ZERO,
+            direction: Point::new(0.0, 1.0),
+            matching: ListMatching::default(),
+        }
+    }
+}
+
+impl MirrorNode {
+    fn affine(inputs: &[Value]) -> Result<Affine, EvalError> {
+        let origin = inputs[1].as_point()?.to_vec2();
+        let direction = inputs[2].as_point()?.to_vec2();
+        let length = direction.hypot();
+        if length == 0.0 {
+            return Err(EvalError::Domain(
+                "The direction of the mirror can't be zero".to_string(),
+            ));
+        }
+        let (x, y) = (direction.x / length, direction.y / length);
+        let reflection = Affine::new([
+            2.0 * x * x - 1.0,
+            2.0 * x * y,
+            2.0 * x * y,
+            2.0 * y * y - 1.0,
+            0.0,
+            0.0,
+        ]);
+        Ok(Affine::translate(origin) * reflection * Affine::translate(-origin))
+    }
+}
+
+impl super::Node for MirrorNode {}
+impl super::NodeInfo for MirrorNode {
+    fn inputs() -> usize {
+        3
+    }
+
+    fn outputs() -> usize {
+        2
+    }
+
+    fn title() -> String {
+        "Mirror".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Any,
+            1 => ValueType::Point,
+            2 => ValueType::Point,
+            _ => unreachable!(),
+        }
+    }
+
+    fn output_type(index: usize) -> ValueType {
+        transform_output_type(index)
+    }
+}
+impl super::NodeDowncast for MirrorNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Mirror(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Mirror(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for MirrorNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => show_geometry_input(pin, ui, scale, results),
+            1 => super::show_point_input("Origin", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).origin
+            }),
+            2 => {
+                super::show_point_input("Direction", pin, ui, scale, snarl, results, |id, snarl| {
+                    &mut super::get_node_mut::<Self>(snarl, id.node).direction
+                })
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for MirrorNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_transform_output(pin, ui)
+    }
+}
+impl super::SolveNode for MirrorNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(Point::ZERO)),
+            1 => Some(Value::Point(self.origin)),
+            2 => Some(Value::Point(self.direction)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        transformed(&inputs[0], Self::affine(inputs)?)
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
+
+/// Chains a list of transforms into one, applying them in order
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug)]
+pub struct ComposeNode {
+    #[serde(default)]
+    matching: ListMatching,
+}
+
+impl super::Node for ComposeNode {}
+impl super::NodeInfo for ComposeNode {
+    fn inputs() -> usize {
+        2
+    }
+
+    fn outputs() -> usize {
+        2
+    }
+
+    fn title() -> String {
+        "Compose".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            0 => ValueType::Any,
+            _ => ValueType::Transform,
+        }
+    }
+
+    fn output_type(index: usize) -> ValueType {
+        transform_output_type(index)
+    }
+}
+impl super::NodeDowncast for ComposeNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::Compose(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::Compose(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for ComposeNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => show_geometry_input(pin, ui, scale, results),
+            1 => super::show_data_input("Transforms", pin, ui, scale, results)
+                .with_fill(crate::TRANSFORM_COLOR),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for ComposeNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_transform_output(pin, ui)
+    }
+}
+impl super::SolveNode for ComposeNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            0 => Some(Value::Point(Point::ZERO)),
+            1 => Some(Value::List(Vec::new())),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let affine = inputs[1]
+            .to_transforms()?
+            .into_iter()
+            .fold(Affine::IDENTITY, |composed, affine| affine * composed);
+        transformed(&inputs[0], affine)
+    }
+
+    fn input_access(&self, index: usize) -> Access {
+        match index {
+            0 => Access::Item,
+            _ => Access::List,
+        }
+    }
+
+    fn matching(&self) -> ListMatching {
+        self.matching
+    }
+
+    fn matching_mut(&mut self) -> Option<&mut ListMatching> {
+        Some(&mut self.matching)
+    }
+}
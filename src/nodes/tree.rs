@@ -0,0 +1,388 @@
+use egui_snarl::ui::PinInfo;
+
+use crate::{
+    solver::{Access, EvalError, Results},
+    tree::{Path, PathMapping},
+    value::{Value, ValueType},
+};
+
+/// Shows the single tree input shared by all nodes restructuring trees
+fn show_tree_input(
+    pin: &egui_snarl::InPin,
+    ui: &mut egui::Ui,
+    scale: f32,
+    results: &Results,
+) -> PinInfo {
+    match pin.id.input {
+        0 => super::show_data_input("Tree", pin, ui, scale, results),
+        _ => unreachable!(),
+    }
+}
+
+fn show_tree_output(ui: &mut egui::Ui) -> PinInfo {
+    ui.label("Tree");
+    PinInfo::circle()
+}
+
+/// Puts every item into a branch of its own
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+pub struct GraftNode;
+
+impl super::Node for GraftNode {}
+impl super::NodeInfo for GraftNode {
+    fn inputs() -> usize {
+        1
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Graft".to_string()
+    }
+}
+impl super::InputNode<super::Nodes> for GraftNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        show_tree_input(pin, ui, scale, results)
+    }
+}
+impl super::OutputNode<super::Nodes> for GraftNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_tree_output(ui)
+    }
+}
+impl super::SolveNode for GraftNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        None
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        Ok(vec![Value::Tree(inputs[0].to_tree().graft())])
+    }
+
+    fn input_access(&self, _index: usize) -> Access {
+        Access::Tree
+    }
+}
+
+/// Collects all items into a single branch
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+pub struct FlattenNode;
+
+impl super::Node for FlattenNode {}
+impl super::NodeInfo for FlattenNode {
+    fn inputs() -> usize {
+        1
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Flatten".to_string()
+    }
+}
+impl super::InputNode<super::Nodes> for FlattenNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        show_tree_input(pin, ui, scale, results)
+    }
+}
+impl super::OutputNode<super::Nodes> for FlattenNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_tree_output(ui)
+    }
+}
+impl super::SolveNode for FlattenNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        None
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        Ok(vec![Value::Tree(inputs[0].to_tree().flatten())])
+    }
+
+    fn input_access(&self, _index: usize) -> Access {
+        Access::Tree
+    }
+}
+
+/// Removes the path indices shared by all branches
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+pub struct SimplifyNode;
+
+impl super::Node for SimplifyNode {}
+impl super::NodeInfo for SimplifyNode {
+    fn inputs() -> usize {
+        1
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Simplify".to_string()
+    }
+}
+impl super::InputNode<super::Nodes> for SimplifyNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        show_tree_input(pin, ui, scale, results)
+    }
+}
+impl super::OutputNode<super::Nodes> for SimplifyNode {
+    fn show_output(
+        _pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        show_tree_output(ui)
+    }
+}
+impl super::SolveNode for SimplifyNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        None
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        Ok(vec![Value::Tree(inputs[0].to_tree().simplify())])
+    }
+
+    fn input_access(&self, _index: usize) -> Access {
+        Access::Tree
+    }
+}
+
+/// Moves items to new branches, following a [`PathMapping`]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PathMapperNode {
+    mapping: String,
+}
+
+impl Default for PathMapperNode {
+    fn default() -> Self {
+        Self {
+            mapping: "{A;B} -> {B;A}".to_string(),
+        }
+    }
+}
+
+impl super::Node for PathMapperNode {}
+impl super::NodeInfo for PathMapperNode {
+    fn inputs() -> usize {
+        1
+    }
+
+    fn outputs() -> usize {
+        1
+    }
+
+    fn title() -> String {
+        "Path Mapper".to_string()
+    }
+}
+impl super::NodeDowncast for PathMapperNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::PathMapper(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::PathMapper(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for PathMapperNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        show_tree_input(pin, ui, scale, results)
+    }
+}
+impl super::OutputNode<super::Nodes> for PathMapperNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        let node = super::get_node_mut::<Self>(snarl, pin.id.node);
+        ui.add(egui::TextEdit::singleline(&mut node.mapping).desired_width(100.0));
+        show_tree_output(ui)
+    }
+}
+impl super::SolveNode for PathMapperNode {
+    fn default_input(&self, _index: usize) -> Option<Value> {
+        None
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let mapping = self.mapping.parse::<PathMapping>()?;
+        Ok(vec![Value::Tree(inputs[0].to_tree().map_paths(&mapping)?)])
+    }
+
+    fn input_access(&self, _index: usize) -> Access {
+        Access::Tree
+    }
+}
+
+/// Picks a single branch out of a tree, and an item out of that branch
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct BranchItemNode {
+    path: String,
+    index: f64,
+}
+
+impl Default for BranchItemNode {
+    fn default() -> Self {
+        Self {
+            path: "{0}".to_string(),
+            index: 0.0,
+        }
+    }
+}
+
+impl super::Node for BranchItemNode {}
+impl super::NodeInfo for BranchItemNode {
+    fn inputs() -> usize {
+        3
+    }
+
+    fn outputs() -> usize {
+        2
+    }
+
+    fn title() -> String {
+        "Branch Item".to_string()
+    }
+
+    fn input_type(index: usize) -> ValueType {
+        match index {
+            1 => ValueType::Text,
+            2 => ValueType::Number,
+            _ => ValueType::Any,
+        }
+    }
+}
+impl super::NodeDowncast for BranchItemNode {
+    fn try_downcast(from: &super::Nodes) -> Option<&Self> {
+        match from {
+            super::Nodes::BranchItem(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn try_downcast_mut(from: &mut super::Nodes) -> Option<&mut Self> {
+        match from {
+            super::Nodes::BranchItem(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+impl super::InputNode<super::Nodes> for BranchItemNode {
+    fn show_input(
+        pin: &egui_snarl::InPin,
+        ui: &mut egui::Ui,
+        scale: f32,
+        snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        results: &Results,
+    ) -> PinInfo {
+        match pin.id.input {
+            0 => super::show_data_input("Tree", pin, ui, scale, results),
+            1 => super::show_text_input("Path", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).path
+            }),
+            2 => super::show_number_input("Index", pin, ui, scale, snarl, results, |id, snarl| {
+                &mut super::get_node_mut::<Self>(snarl, id.node).index
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+impl super::OutputNode<super::Nodes> for BranchItemNode {
+    fn show_output(
+        pin: &egui_snarl::OutPin,
+        ui: &mut egui::Ui,
+        _scale: f32,
+        _snarl: &mut egui_snarl::Snarl<super::Nodes>,
+        _results: &Results,
+    ) -> PinInfo {
+        match pin.id.output {
+            0 => ui.label("Branch"),
+            1 => ui.label("Item"),
+            _ => unreachable!(),
+        };
+        PinInfo::circle()
+    }
+}
+impl super::SolveNode for BranchItemNode {
+    fn default_input(&self, index: usize) -> Option<Value> {
+        match index {
+            1 => Some(Value::Text(self.path.clone())),
+            2 => Some(Value::Number(self.index)),
+            _ => None,
+        }
+    }
+
+    fn solve(&self, inputs: &[Value]) -> Result<Vec<Value>, EvalError> {
+        let tree = inputs[0].to_tree();
+        let path = inputs[1].as_text()?.parse::<Path>()?;
+        let index = inputs[2].as_count()?;
+        let branch = tree
+            .branch(&path)
+            .ok_or_else(|| EvalError::Domain(format!("There is no branch {path}")))?;
+        let item = branch.items.get(index).cloned().ok_or_else(|| {
+            EvalError::Domain(format!("Branch {path} has no item at index {index}"))
+        })?;
+        Ok(vec![Value::List(branch.items.clone()), item])
+    }
+
+    fn input_access(&self, index: usize) -> Access {
+        match index {
+            0 => Access::Tree,
+            _ => Access::Item,
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! Non-uniform rational B-spline curves, which describe circles and
+//! other conic sections exactly, unlike the curves of [`crate::curves`]
+
+use piet::kurbo::{Affine, BezPath, CubicBez, ParamCurve, Point, Vec2};
+
+use crate::solver::EvalError;
+
+/// A NURBS curve of any degree with a control point, a weight and a
+/// knot vector it was checked against. Evaluated in the parameter
+/// range of its knots, see [`Nurbs::domain`]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(try_from = "Unchecked")]
+pub struct Nurbs {
+    control_points: Vec<Point>,
+    weights: Vec<f64>,
+    degree: usize,
+    knots: Vec<f64>,
+}
+
+/// The fields of a deserialized curve before they are checked
+#[derive(serde::Deserialize)]
+struct Unchecked {
+    control_points: Vec<Point>,
+    weights: Vec<f64>,
+    degree: usize,
+    knots: Vec<f64>,
+}
+
+impl TryFrom<Unchecked> for Nurbs {
+    type Error = EvalError;
+
+    fn try_from(curve: Unchecked) -> Result<Self, Self::Error> {
+        Nurbs::new(
+            curve.control_points,
+            Some(curve.weights),
+            curve.degree,
+            Some(curve.knots),
+        )
+    }
+}
+
+/// Point and its homogeneous weight, in which a rational
+/// curve is a plain polynomial one
+#[derive(Clone, Copy)]
+struct Homogeneous {
+    x: f64,
+    y: f64,
+    w: f64,
+}
+
+impl Homogeneous {
+    fn lerp(self, other: Self, t: f64) -> Self {
+        Self {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+            w: self.w + (other.w - self.w) * t,
+        }
+    }
+}
+
+impl Nurbs {
+    /// Largest distance the Bezier approximation may deviate by
+    pub const TOLERANCE: f64 = 1e-3;
+
+    /// Checks and builds a curve. All weights are 1 if `None` is given,
+    /// which makes a plain B-spline. Without knots, the knots are spaced
+    /// uniformly and clamped, so the curve starts at the first and
+    /// ends at the last control point
+    pub fn new(
+        control_points: Vec<Point>,
+        weights: Option<Vec<f64>>,
+        degree: usize,
+        knots: Option<Vec<f64>>,
+    ) -> Result<Self, EvalError> {
+        let count = control_points.len();
+        if degree == 0 {
+            return Err(EvalError::Domain(
+                "The degree of a curve has to be at least 1".to_string(),
+            ));
+        }
+        if count <= degree {
+            return Err(EvalError::Domain(format!(
+                "A curve of degree {degree} needs at least {} control points, but got {count}",
+                degree.saturating_add(1)
+            )));
+        }
+        // With fewer degrees than points, no sum below can overflow
+        let weights = weights.unwrap_or_else(|| vec![1.0; count]);
+        if weights.len() != count {
+            return Err(EvalError::Domain(format!(
+                "Every control point needs a weight, but got {} weights for {count} points",
+                weights.len()
+            )));
+        }
+        if let Some(weight) = weights.iter().find(|w| !(w.is_finite() && **w > 0.0)) {
+            return Err(EvalError::Domain(format!(
+                "Weights have to be positive, but one is {weight}"
+            )));
+        }
+        let knots = knots.unwrap_or_else(|| clamped_knots(count, degree));
+        if knots.len() != count + degree + 1 {
+            return Err(EvalError::Domain(format!(
+                "A curve of degree {degree} with {count} control points needs {} knots, but got {}",
+                count + degree + 1,
+                knots.len()
+            )));
+        }
+        if knots.iter().any(|knot| !knot.is_finite())
+            || knots.windows(2).any(|pair| pair[0] > pair[1])
+        {
+            return Err(EvalError::Domain(
+                "Knots have to be finite and must not decrease".to_string(),
+            ));
+        }
+        if knots[degree] >= knots[count] {
+            return Err(EvalError::Domain(
+                "The knots leave no range to evaluate the curve in".to_string(),
+            ));
+        }
+        Ok(Self {
+            control_points,
+            weights,
+            degree,
+            knots,
+        })
+    }
+
+    pub fn control_points(&self) -> &[Point] {
+        &self.control_points
+    }
+
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    pub fn knots(&self) -> &[f64] {
+        &self.knots
+    }
+
+    /// First and last parameter the curve is defined for
+    pub fn domain(&self) -> (f64, f64) {
+        (
+            self.knots[self.degree],
+            self.knots[self.control_points.len()],
+        )
+    }
+
+    /// The parameter at a fraction of the domain, 0 for the start
+    /// and 1 for the end of the curve
+    pub fn parameter_at(&self, fraction: f64) -> f64 {
+        let (start, end) = self.domain();
+        start + (end - start) * fraction
+    }
+
+    /// The point at a parameter, which is clamped to the domain
+    pub fn point_at(&self, parameter: f64) -> Point {
+        let parameter = self.clamp(parameter);
+        self.evaluate(self.span(parameter), parameter).0
+    }
+
+    /// The first derivative at a parameter, which is clamped to the
+    /// domain. Points along the curve, with a length of how fast
+    /// the point moves as the parameter grows
+    pub fn derivative_at(&self, parameter: f64) -> Vec2 {
+        let parameter = self.clamp(parameter);
+        self.evaluate(self.span(parameter), parameter).1
+    }
+
+    /// The curve moved by a transform, which is exact
+    /// as NURBS only depend on their control points
+    pub fn transform(&self, affine: Affine) -> Self {
+        Self {
+            control_points: self
+                .control_points
+                .iter()
+                .map(|point| affine * *point)
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Cubic Bezier segments no further than `tolerance` from the curve,
+    /// split at every knot so corners of repeated knots are kept
+    pub fn to_bezier(&self, tolerance: f64) -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to(self.point_at(self.domain().0));
+        for span in self.degree..self.control_points.len() {
+            let (start, end) = (self.knots[span], self.knots[span + 1]);
+            if start < end {
+                self.fit(&mut path, span, start, end, tolerance, 0);
+            }
+        }
+        path
+    }
+
+    /// Appends one cubic matching the position and derivative at both
+    /// ends of a piece of a span, halving the piece until it is close
+    fn fit(
+        &self,
+        path: &mut BezPath,
+        span: usize,
+        start: f64,
+        end: f64,
+        tolerance: f64,
+        depth: usize,
+    ) {
+        let (p0, d0) = self.evaluate(span, start);
+        let (p3, d1) = self.evaluate(span, end);
+        let third = (end - start) / 3.0;
+        let cubic = CubicBez::new(p0, p0 + d0 * third, p3 - d1 * third, p3);
+        let close = [0.25, 0.5, 0.75].iter().all(|t| {
+            let exact = self.evaluate(span, start + (end - start) * t).0;
+            cubic.eval(*t).distance(exact) <= tolerance
+        });
+        if close || depth >= 16 {
+            path.curve_to(cubic.p1, cubic.p2, cubic.p3);
+        } else {
+            let middle = (start + end) / 2.0;
+            self.fit(path, span, start, middle, tolerance, depth + 1);
+            self.fit(path, span, middle, end, tolerance, depth + 1);
+        }
+    }
+
+    fn clamp(&self, parameter: f64) -> f64 {
+        let (start, end) = self.domain();
+        parameter.clamp(start, end)
+    }
+
+    /// The index of the last knot at or before the parameter
+    /// which starts a span of non-zero length
+    fn span(&self, parameter: f64) -> usize {
+        let last = self.control_points.len() - 1;
+        let mut span = self.degree;
+        while span < last && self.knots[span + 1] <= parameter {
+            span += 1;
+        }
+        // Trailing knots equal to the end belong to the span before them
+        while self.knots[span] == self.knots[span + 1] {
+            span -= 1;
+        }
+        span
+    }
+
+    /// Position and first derivative using the polynomial of a span,
+    /// by de Boor's algorithm in homogeneous coordinates
+    fn evaluate(&self, span: usize, parameter: f64) -> (Point, Vec2) {
+        let homogeneous = self
+            .control_points
+            .iter()
+            .zip(&self.weights)
+            .map(|(point, weight)| Homogeneous {
+                x: point.x * weight,
+                y: point.y * weight,
+                w: *weight,
+            })
+            .collect::<Vec<_>>();
+        let value = de_boor(&homogeneous, &self.knots, self.degree, span, parameter);
+
+        // The derivative is a spline of one degree less, with the
+        // control points scaled differences of the original ones
+        let degree = self.degree as f64;
+        let differences = homogeneous
+            .windows(2)
+            .enumerate()
+            .map(|(index, pair)| {
+                let length = self.knots[index + self.degree + 1] - self.knots[index + 1];
+                let scale = if length > 0.0 { degree / length } else { 0.0 };
+                Homogeneous {
+                    x: (pair[1].x - pair[0].x) * scale,
+                    y: (pair[1].y - pair[0].y) * scale,
+                    w: (pair[1].w - pair[0].w) * scale,
+                }
+            })
+            .collect::<Vec<_>>();
+        let knots = &self.knots[1..self.knots.len() - 1];
+        let slope = de_boor(&differences, knots, self.degree - 1, span - 1, parameter);
+
+        let point = Point::new(value.x / value.w, value.y / value.w);
+        let derivative = Vec2::new(
+            (slope.x - slope.w * point.x) / value.w,
+            (slope.y - slope.w * point.y) / value.w,
+        );
+        (point, derivative)
+    }
+}
+
+impl std::fmt::Display for Nurbs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "NURBS of degree {} with {} control points",
+            self.degree,
+            self.control_points.len()
+        )
+    }
+}
+
+/// Knots which make the curve touch its first and last control point,
+/// spaced evenly in between over a domain from 0 to 1
+fn clamped_knots(count: usize, degree: usize) -> Vec<f64> {
+    let inner = count - degree;
+    (0..count + degree + 1)
+        .map(|index| (index.saturating_sub(degree).min(inner)) as f64 / inner as f64)
+        .collect()
+}
+
+fn de_boor(
+    points: &[Homogeneous],
+    knots: &[f64],
+    degree: usize,
+    span: usize,
+    parameter: f64,
+) -> Homogeneous {
+    let mut d = points[span - degree..=span].to_vec();
+    for r in 1..=degree {
+        for j in (r..=degree).rev() {
+            let left = knots[j + span - degree];
+            let right = knots[j + 1 + span - r];
+            let alpha = if right > left {
+                (parameter - left) / (right - left)
+            } else {
+                0.0
+            };
+            d[j] = d[j - 1].lerp(d[j], alpha);
+        }
+    }
+    d[degree]
+}
@@ -0,0 +1,216 @@
+use std::path::{Path, PathBuf};
+
+use egui_snarl::{NodeId, OutPinId, Snarl};
+use piet::{
+    kurbo::{BezPath, Shape},
+    RenderContext,
+};
+
+use crate::{nodes::Nodes, shapes::Shapes, solver::Results, style::Style, value::Value};
+
+pub mod pdf;
+pub mod plot;
+pub mod raster;
+
+pub use plot::{Plot, PlotOptions};
+pub use raster::RasterOptions;
+
+/// Settings of all backends, each of which only looks at its own
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    pub raster: RasterOptions,
+    pub plot: PlotOptions,
+}
+
+/// File formats a drawing can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Svg,
+    Png,
+    Pdf,
+    Gcode,
+    Hpgl,
+}
+
+impl Format {
+    /// The format matching the extension of a file, svg if there is none
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            None | Some("svg") => Ok(Self::Svg),
+            Some("png") => Ok(Self::Png),
+            Some("pdf") => Ok(Self::Pdf),
+            Some("gcode" | "nc") => Ok(Self::Gcode),
+            Some("hpgl" | "plt") => Ok(Self::Hpgl),
+            Some(extension) => anyhow::bail!("Can't export drawings as {extension}"),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Png => "png",
+            Self::Pdf => "pdf",
+            Self::Gcode => "gcode",
+            Self::Hpgl => "hpgl",
+        }
+    }
+
+    /// Formats driving a pen plotter, which are optimized before writing
+    pub fn is_plot(self) -> bool {
+        matches!(self, Self::Gcode | Self::Hpgl)
+    }
+}
+
+/// `out.svg` with a suffix of `1` becomes `out-1.svg`
+pub fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(extension) => format!("{stem}-{suffix}.{}", extension.to_string_lossy()),
+        None => format!("{stem}-{suffix}"),
+    };
+    path.with_file_name(name)
+}
+
+/// What one unit of the canvas size stands for, which
+/// decides the physical size of exported pages
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// Css pixels, 96 of which make an inch
+    #[default]
+    Px,
+    Mm,
+    In,
+}
+
+impl Unit {
+    pub const ALL: [Self; 3] = [Self::Px, Self::Mm, Self::In];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Px => "px",
+            Self::Mm => "mm",
+            Self::In => "in",
+        }
+    }
+
+    /// Typographic points per unit, as used by pdf
+    pub fn points(self) -> f64 {
+        match self {
+            Self::Px => 72.0 / 96.0,
+            Self::Mm => 72.0 / 25.4,
+            Self::In => 72.0,
+        }
+    }
+}
+
+/// Everything needed to render the contents of a canvas,
+/// independent of the backend used to do so
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct Drawing {
+    pub size: piet::kurbo::Size,
+    pub shapes: Vec<Shapes>,
+    #[serde(default)]
+    pub unit: Unit,
+}
+
+impl Drawing {
+    pub fn new(size: piet::kurbo::Size, shapes: Vec<Shapes>) -> Self {
+        Self {
+            size,
+            shapes,
+            unit: Unit::default(),
+        }
+    }
+
+    pub fn with_unit(self, unit: Unit) -> Self {
+        Self { unit, ..self }
+    }
+
+    /// Draws all shapes on any piet render context
+    pub fn draw(&self, rc: &mut impl RenderContext) {
+        rc.clear(None, piet::Color::WHITE);
+        for shape in &self.shapes {
+            let style = shape.style();
+            if let Some(fill) = style.fill_color() {
+                rc.fill(shape, &fill.to_piet());
+            }
+            if let Some(stroke) = style.stroke_color() {
+                rc.stroke_styled(
+                    shape,
+                    &stroke.to_piet(),
+                    style.width,
+                    &style.to_piet_stroke(),
+                );
+            }
+        }
+    }
+
+    /// The outlines of all shapes together with their style,
+    /// which is what every backend without piet support draws
+    pub fn paths(&self, tolerance: f64) -> impl Iterator<Item = (BezPath, &Style)> + '_ {
+        self.shapes.iter().map(move |shape| {
+            let path = shape.path_elements(tolerance).collect();
+            (path, shape.style())
+        })
+    }
+
+    pub fn to_svg(&self) -> Vec<u8> {
+        let mut rc = piet_svg::RenderContext::new(self.size);
+        self.draw(&mut rc);
+        let mut buffer: Vec<u8> = Vec::new();
+        rc.write(&mut buffer).expect("Write worked");
+        buffer
+    }
+
+    /// Encodes the drawing in the given format
+    pub fn export(&self, format: Format, options: &ExportOptions) -> anyhow::Result<Vec<u8>> {
+        match format {
+            Format::Svg => Ok(self.to_svg()),
+            Format::Png => self.to_png(&options.raster),
+            Format::Pdf => Ok(self.to_pdf()),
+            Format::Gcode => Ok(Plot::new(self, &options.plot)
+                .to_gcode(&options.plot)
+                .into_bytes()),
+            Format::Hpgl => Ok(Plot::new(self, &options.plot)
+                .to_hpgl(&options.plot)
+                .into_bytes()),
+        }
+    }
+}
+
+/// The drawings of every canvas in the graph, ordered by node id.
+/// A canvas can have many drawings due to list matching
+pub fn canvas_drawings<'a>(
+    snarl: &Snarl<Nodes>,
+    results: &'a Results,
+) -> Vec<(NodeId, Vec<&'a Drawing>)> {
+    let mut canvases = snarl
+        .node_ids()
+        .filter(|(_, node)| matches!(node, Nodes::Canvas(_)))
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    canvases.sort();
+    canvases
+        .into_iter()
+        .map(|node| {
+            let value = results.get(OutPinId { node, output: 0 });
+            (node, value.map(drawings).unwrap_or_default())
+        })
+        .collect()
+}
+
+/// All drawings in a canvas output, which may be a list or tree of drawings
+pub fn drawings(value: &Value) -> Vec<&Drawing> {
+    match value {
+        Value::Drawing(drawing) => vec![drawing],
+        Value::List(values) => values.iter().flat_map(drawings).collect(),
+        Value::Tree(tree) => tree.items().flat_map(drawings).collect(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::{BTreeMap, HashMap};
+
+use pdf_writer::{
+    types::{LineCapStyle, LineJoinStyle},
+    Content, Finish, Name, Pdf, Rect, Ref,
+};
+use piet::kurbo::{BezPath, PathEl};
+
+use super::Drawing;
+use crate::style::{LineCap, LineJoin};
+
+/// Writes every drawing onto a page of its own, sized by
+/// the canvas size interpreted in the unit of the drawing
+pub fn to_pdf(drawings: &[&Drawing]) -> Vec<u8> {
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    // Every page is followed by its content stream
+    let page_ids = (0..drawings.len())
+        .map(|index| Ref::new(3 + 2 * index as i32))
+        .collect::<Vec<_>>();
+    // Transparency needs graphics states, one for every pair of fill
+    // and stroke alpha used anywhere, which follow after the pages
+    let mut next_id = 3 + 2 * drawings.len() as i32;
+    let mut alpha_states: HashMap<(u16, u16), (String, Ref)> = HashMap::new();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .kids(page_ids.iter().copied())
+        .count(drawings.len() as i32);
+
+    for (drawing, page_id) in drawings.iter().zip(page_ids) {
+        let content_id = Ref::new(page_id.get() + 1);
+        let scale = drawing.unit.points() as f32;
+        let width = drawing.size.width as f32 * scale;
+        let height = drawing.size.height as f32 * scale;
+
+        let mut content = Content::new();
+        let mut used_states = BTreeMap::new();
+        // Canvas coordinates grow downwards, pdf coordinates upwards
+        content.transform([scale, 0.0, 0.0, -scale, 0.0, height]);
+        for (path, style) in drawing.paths(0.1) {
+            let fill = style.fill_color();
+            let stroke = style.stroke_color();
+            if fill.is_none() && stroke.is_none() {
+                continue;
+            }
+            content.save_state();
+
+            let alphas = (
+                fill.map_or(1.0, |color| color.a),
+                stroke.map_or(1.0, |color| color.a),
+            );
+            if alphas != (1.0, 1.0) {
+                let quantize =
+                    |alpha: f64| (alpha.clamp(0.0, 1.0) * u16::MAX as f64).round() as u16;
+                let (name, id) = alpha_states
+                    .entry((quantize(alphas.0), quantize(alphas.1)))
+                    .or_insert_with(|| {
+                        let id = Ref::new(next_id);
+                        next_id += 1;
+                        pdf.ext_graphics(id)
+                            .non_stroking_alpha(alphas.0 as f32)
+                            .stroking_alpha(alphas.1 as f32);
+                        (format!("Alpha{}", id.get()), id)
+                    });
+                content.set_parameters(Name(name.as_bytes()));
+                used_states.insert(name.clone(), *id);
+            }
+
+            if let Some(color) = fill {
+                content.set_fill_rgb(color.r as f32, color.g as f32, color.b as f32);
+            }
+            if let Some(color) = stroke {
+                content.set_stroke_rgb(color.r as f32, color.g as f32, color.b as f32);
+                content.set_line_width(style.width as f32);
+                content.set_line_cap(match style.cap {
+                    LineCap::Butt => LineCapStyle::ButtCap,
+                    LineCap::Round => LineCapStyle::RoundCap,
+                    LineCap::Square => LineCapStyle::ProjectingSquareCap,
+                });
+                content.set_line_join(match style.join {
+                    LineJoin::Miter => LineJoinStyle::MiterJoin,
+                    LineJoin::Round => LineJoinStyle::RoundJoin,
+                    LineJoin::Bevel => LineJoinStyle::BevelJoin,
+                });
+                content.set_miter_limit(LineJoin::MITER_LIMIT as f32);
+                if let Some(dashes) = style.dashes() {
+                    content.set_dash_pattern(dashes.into_iter().map(|d| d as f32), 0.0);
+                }
+            }
+
+            write_path(&mut content, &path);
+            match (fill, stroke) {
+                (Some(_), Some(_)) => content.fill_nonzero_and_stroke(),
+                (Some(_), None) => content.fill_nonzero(),
+                (None, _) => content.stroke(),
+            };
+            content.restore_state();
+        }
+        pdf.stream(content_id, &content.finish());
+
+        let mut page = pdf.page(page_id);
+        page.parent(page_tree_id)
+            .media_box(Rect::new(0.0, 0.0, width, height))
+            .contents(content_id);
+        let mut resources = page.resources();
+        let mut states = resources.ext_g_states();
+        for (name, id) in &used_states {
+            states.pair(Name(name.as_bytes()), *id);
+        }
+        states.finish();
+        resources.finish();
+        page.finish();
+    }
+
+    pdf.finish()
+}
+
+fn write_path(content: &mut Content, path: &BezPath) {
+    let mut last = piet::kurbo::Point::ZERO;
+    // Closing a subpath moves back to where it started
+    let mut start = piet::kurbo::Point::ZERO;
+    for element in path.elements() {
+        match *element {
+            PathEl::MoveTo(p) => {
+                content.move_to(p.x as f32, p.y as f32);
+                last = p;
+                start = p;
+            }
+            PathEl::LineTo(p) => {
+                content.line_to(p.x as f32, p.y as f32);
+                last = p;
+            }
+            PathEl::QuadTo(p1, p2) => {
+                // Pdf has no quadratic curves, but every one is also a cubic
+                let c1 = last + (p1 - last) * (2.0 / 3.0);
+                let c2 = p2 + (p1 - p2) * (2.0 / 3.0);
+                content.cubic_to(
+                    c1.x as f32,
+                    c1.y as f32,
+                    c2.x as f32,
+                    c2.y as f32,
+                    p2.x as f32,
+                    p2.y as f32,
+                );
+                last = p2;
+            }
+            PathEl::CurveTo(p1, p2, p3) => {
+                content.cubic_to(
+                    p1.x as f32,
+                    p1.y as f32,
+                    p2.x as f32,
+                    p2.y as f32,
+                    p3.x as f32,
+                    p3.y as f32,
+                );
+                last = p3;
+            }
+            PathEl::ClosePath => {
+                content.close_path();
+                last = start;
+            }
+        }
+    }
+}
+
+impl Drawing {
+    pub fn to_pdf(&self) -> Vec<u8> {
+        to_pdf(&[self])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quads_after_a_closed_subpath_start_at_its_beginning() {
+        // Svg allows segments right after closing, kurbo's builder doesn't
+        let path = BezPath::from_vec(vec![
+            PathEl::MoveTo((0.0, 0.0).into()),
+            PathEl::LineTo((30.0, 0.0).into()),
+            PathEl::ClosePath,
+            PathEl::QuadTo((0.0, 30.0).into(), (30.0, 30.0).into()),
+        ]);
+        let mut content = Content::new();
+        write_path(&mut content, &path);
+        let content = String::from_utf8(content.finish()).unwrap();
+        assert_eq!(content, "0 0 m\n30 0 l\nh\n0 20 10 30 30 30 c");
+    }
+}
@@ -0,0 +1,455 @@
+use std::{collections::HashSet, fmt::Write};
+
+use piet::kurbo::{PathEl, Point};
+
+use super::{Drawing, Unit};
+
+/// Dash patterns repeating in less than this many mm are plotted as
+/// solid lines. A pen can't draw them anyway, and they would split
+/// every line into a huge number of pieces
+const MIN_DASH_PERIOD: f64 = 0.1;
+
+/// How drawings are turned into instructions for a pen plotter
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct PlotOptions {
+    /// Speed while drawing, in mm per minute
+    pub feed_rate: f64,
+    /// Speed while the pen is up, in mm per minute
+    pub travel_rate: f64,
+    /// G-code lifting the pen
+    pub pen_up: String,
+    /// G-code lowering the pen
+    pub pen_down: String,
+    /// Lines ending closer than this, in mm,
+    /// are drawn without lifting the pen
+    pub merge_distance: f64,
+    /// Maximum distance between curves and the lines replacing them, in mm
+    pub tolerance: f64,
+}
+
+impl Default for PlotOptions {
+    fn default() -> Self {
+        Self {
+            feed_rate: 1500.0,
+            travel_rate: 3000.0,
+            pen_up: "G0 Z1".to_string(),
+            pen_down: "G0 Z0".to_string(),
+            merge_distance: 0.1,
+            tolerance: 0.05,
+        }
+    }
+}
+
+/// Lines to draw in the order they are drawn, in mm with
+/// the origin in the lower left corner like most plotters
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plot {
+    pub lines: Vec<Vec<Point>>,
+    /// Travel distance before the lines were reordered, in mm
+    pub unoptimized_travel: f64,
+}
+
+impl Plot {
+    /// Flattens all shapes of the drawing into lines, drops duplicates
+    /// and orders the lines so the pen travels as little as possible
+    pub fn new(drawing: &Drawing, options: &PlotOptions) -> Self {
+        let mm = mm_per_unit(drawing.unit);
+        let height = drawing.size.height * mm;
+        let mut lines = Vec::new();
+        for (path, style) in drawing.paths(options.tolerance / mm) {
+            // A pen can't fill, and has the width of its tip
+            if style.stroke_color().is_none() {
+                continue;
+            }
+            let mut polylines = Vec::new();
+            let mut line: Vec<Point> = Vec::new();
+            let mut start = Point::ZERO;
+            piet::kurbo::flatten(path, options.tolerance / mm, |element| match element {
+                PathEl::MoveTo(p) => {
+                    if line.len() > 1 {
+                        polylines.push(std::mem::take(&mut line));
+                    }
+                    line.clear();
+                    line.push(p);
+                    start = p;
+                }
+                PathEl::LineTo(p) => line.push(p),
+                PathEl::ClosePath => line.push(start),
+                PathEl::QuadTo(..) | PathEl::CurveTo(..) => unreachable!("Paths are flattened"),
+            });
+            if line.len() > 1 {
+                polylines.push(line);
+            }
+            let dashes = style
+                .dashes()
+                .filter(|dashes| dashes.iter().sum::<f64>() * mm >= MIN_DASH_PERIOD);
+            match dashes {
+                Some(dashes) => lines.extend(
+                    polylines
+                        .into_iter()
+                        .flat_map(|polyline| dash(&polyline, &dashes)),
+                ),
+                None => lines.extend(polylines),
+            }
+        }
+        // Canvas coordinates grow downwards, plotter coordinates upwards
+        let lines = lines
+            .into_iter()
+            .map(|line| {
+                line.into_iter()
+                    .map(|p| Point::new(p.x * mm, height - p.y * mm))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let lines = deduplicate(lines, options.merge_distance);
+        let unoptimized_travel = travel(&lines);
+        let lines = reorder(lines, options.merge_distance);
+        Self {
+            lines,
+            unoptimized_travel,
+        }
+    }
+
+    /// Distance with the pen down, in mm
+    pub fn draw_distance(&self) -> f64 {
+        self.lines
+            .iter()
+            .flat_map(|line| line.windows(2))
+            .map(|segment| segment[0].distance(segment[1]))
+            .sum()
+    }
+
+    /// Distance with the pen up, starting and ending at the origin, in mm
+    pub fn travel_distance(&self) -> f64 {
+        travel(&self.lines)
+    }
+
+    /// Summary of the distances, for showing them to the user
+    pub fn report(&self) -> String {
+        let count = self.lines.len();
+        format!(
+            "{count} {}, drawing {:.1} mm and travelling {:.1} mm (was {:.1} mm)",
+            if count == 1 { "line" } else { "lines" },
+            self.draw_distance(),
+            self.travel_distance(),
+            self.unoptimized_travel
+        )
+    }
+
+    pub fn to_gcode(&self, options: &PlotOptions) -> String {
+        let mut gcode = String::new();
+        let _ = writeln!(gcode, "G21 ; millimetres");
+        let _ = writeln!(gcode, "G90 ; absolute coordinates");
+        let _ = writeln!(gcode, "{}", options.pen_up);
+        for line in &self.lines {
+            let [first, rest @ ..] = line.as_slice() else {
+                continue;
+            };
+            let _ = writeln!(
+                gcode,
+                "G0 X{:.3} Y{:.3} F{}",
+                first.x, first.y, options.travel_rate
+            );
+            let _ = writeln!(gcode, "{}", options.pen_down);
+            for point in rest {
+                let _ = writeln!(
+                    gcode,
+                    "G1 X{:.3} Y{:.3} F{}",
+                    point.x, point.y, options.feed_rate
+                );
+            }
+            let _ = writeln!(gcode, "{}", options.pen_up);
+        }
+        let _ = writeln!(gcode, "G0 X0 Y0 F{}", options.travel_rate);
+        gcode
+    }
+
+    /// HPGL knows no feed rates, the pen speed
+    /// is set with the velocity instruction instead
+    pub fn to_hpgl(&self, options: &PlotOptions) -> String {
+        // Plotter units are 0.025 mm, velocities are in cm per second
+        let unit = |value: f64| (value * 40.0).round() as i64;
+        let mut hpgl = String::new();
+        let _ = write!(hpgl, "IN;SP1;VS{:.1};", options.feed_rate / 600.0);
+        for line in &self.lines {
+            let [first, rest @ ..] = line.as_slice() else {
+                continue;
+            };
+            let _ = write!(hpgl, "PU{},{};PD", unit(first.x), unit(first.y));
+            let points = rest
+                .iter()
+                .map(|p| format!("{},{}", unit(p.x), unit(p.y)))
+                .collect::<Vec<_>>();
+            let _ = write!(hpgl, "{};", points.join(","));
+        }
+        hpgl.push_str("PU0,0;SP0;\n");
+        hpgl
+    }
+}
+
+fn mm_per_unit(unit: Unit) -> f64 {
+    unit.points() * 25.4 / 72.0
+}
+
+/// Splits a line into the dashes of a pattern of alternating
+/// dash and gap lengths, which has an even number of lengths
+fn dash(line: &[Point], dashes: &[f64]) -> Vec<Vec<Point>> {
+    let mut pieces = Vec::new();
+    let mut index = 0;
+    let mut remaining = dashes[0];
+    let mut current = vec![line[0]];
+    for segment in line.windows(2) {
+        let (mut from, to) = (segment[0], segment[1]);
+        let mut length = from.distance(to);
+        // Dashes ending right at a corner end there, instead of
+        // leaving a piece without length on the next segment
+        while length >= remaining {
+            let split = from.lerp(to, remaining / length);
+            length -= remaining;
+            from = split;
+            // Even indices are dashes, odd ones are gaps
+            if index % 2 == 0 {
+                current.push(split);
+                pieces.push(std::mem::take(&mut current));
+            } else {
+                current = vec![split];
+            }
+            index = (index + 1) % dashes.len();
+            remaining = dashes[index];
+        }
+        remaining -= length;
+        if index % 2 == 0 {
+            current.push(to);
+        }
+    }
+    if index % 2 == 0 {
+        pieces.push(current);
+    }
+    pieces.retain(|piece| piece.len() > 1);
+    pieces
+}
+
+/// Pen up distance when drawing the lines in the given order
+fn travel(lines: &[Vec<Point>]) -> f64 {
+    let mut position = Point::ZERO;
+    let mut distance = 0.0;
+    for line in lines {
+        if let (Some(first), Some(last)) = (line.first(), line.last()) {
+            distance += position.distance(*first);
+            position = *last;
+        }
+    }
+    distance + position.distance(Point::ZERO)
+}
+
+/// Drops lines drawn before, in either direction
+fn deduplicate(lines: Vec<Vec<Point>>, precision: f64) -> Vec<Vec<Point>> {
+    let precision = precision.max(1e-6);
+    let key = |points: &mut dyn Iterator<Item = &Point>| {
+        points
+            .map(|p| {
+                (
+                    (p.x / precision).round() as i64,
+                    (p.y / precision).round() as i64,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut seen = HashSet::new();
+    lines
+        .into_iter()
+        .filter(|line| {
+            let forward = key(&mut line.iter());
+            let backward = key(&mut line.iter().rev());
+            !seen.contains(&backward) && seen.insert(forward)
+        })
+        .collect()
+}
+
+/// Greedily picks the closest line to continue with, in whichever
+/// direction starts closer, and joins lines which touch
+fn reorder(mut lines: Vec<Vec<Point>>, merge_distance: f64) -> Vec<Vec<Point>> {
+    let mut ordered: Vec<Vec<Point>> = Vec::with_capacity(lines.len());
+    let mut position = Point::ZERO;
+    while !lines.is_empty() {
+        let (index, reversed, distance) = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(index, line)| {
+                let first = line.first().expect("Lines have at least two points");
+                let last = line.last().expect("Lines have at least two points");
+                [
+                    (index, false, position.distance(*first)),
+                    (index, true, position.distance(*last)),
+                ]
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .expect("There are lines left");
+        let mut line = lines.swap_remove(index);
+        if reversed {
+            line.reverse();
+        }
+        position = *line.last().expect("Lines have at least two points");
+        match ordered.last_mut() {
+            Some(previous) if distance <= merge_distance => previous.extend(line),
+            _ => ordered.push(line),
+        }
+    }
+    ordered.into_iter().map(merge_segments).collect()
+}
+
+/// Joins consecutive segments going in the same direction,
+/// and drops segments without any length
+fn merge_segments(line: Vec<Point>) -> Vec<Point> {
+    let mut merged: Vec<Point> = Vec::with_capacity(line.len());
+    for point in line {
+        if merged
+            .last()
+            .is_some_and(|last| last.distance(point) < 1e-9)
+        {
+            continue;
+        }
+        if let [.., a, b] = merged.as_slice() {
+            let (ab, bc) = (*b - *a, point - *b);
+            let collinear = ab.cross(bc).abs() <= 1e-9 * ab.hypot() * bc.hypot();
+            if collinear && ab.dot(bc) > 0.0 {
+                merged.pop();
+            }
+        }
+        merged.push(point);
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(points: &[(f64, f64)]) -> Vec<Point> {
+        points.iter().copied().map(Point::from).collect()
+    }
+
+    #[test]
+    fn reversed_duplicates_are_dropped() {
+        let square = line(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        let mut reversed = square.clone();
+        reversed.reverse();
+        let nearly = line(&[(0.01, 0.0), (10.0, 0.01), (10.0, 10.0)]);
+        let other = line(&[(0.0, 0.0), (0.0, 10.0)]);
+        let lines = deduplicate(vec![square.clone(), reversed, nearly, other.clone()], 0.1);
+        assert_eq!(lines, vec![square, other]);
+    }
+
+    #[test]
+    fn repeated_shapes_are_drawn_once() {
+        // Like a Repeat Shape node, with one copy drawn the other way
+        let line = piet::kurbo::Line::new((10.0, 10.0), (50.0, 30.0));
+        let shapes = vec![
+            crate::shapes::Shapes::Line(line),
+            crate::shapes::Shapes::Line(line),
+            crate::shapes::Shapes::Line(piet::kurbo::Line::new(line.p1, line.p0)),
+        ];
+        let drawing = Drawing::new(piet::kurbo::Size::new(100.0, 100.0), shapes);
+        let plot = Plot::new(&drawing, &PlotOptions::default());
+        assert_eq!(plot.lines.len(), 1);
+        let mm = mm_per_unit(drawing.unit);
+        assert!((plot.draw_distance() - line.length() * mm).abs() < 1e-9);
+    }
+
+    #[test]
+    fn collinear_segments_merge_into_one() {
+        let merged = merge_segments(line(&[
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 0.0),
+            (5.0, 0.0),
+            (5.0, 3.0),
+        ]));
+        assert_eq!(merged, line(&[(0.0, 0.0), (5.0, 0.0), (5.0, 3.0)]));
+        // Turning back on the same line is drawn as it is
+        let back = line(&[(0.0, 0.0), (2.0, 0.0), (1.0, 0.0)]);
+        assert_eq!(merge_segments(back.clone()), back);
+    }
+
+    #[test]
+    fn touching_lines_are_joined() {
+        let lines = vec![
+            line(&[(0.0, 0.0), (1.0, 0.0)]),
+            line(&[(2.0, 0.0), (1.0, 0.0)]),
+        ];
+        assert_eq!(reorder(lines, 0.1), vec![line(&[(0.0, 0.0), (2.0, 0.0)])]);
+    }
+
+    #[test]
+    fn reordering_reduces_travel() {
+        // Short strokes along a row, visited in a scrambled order
+        let lines = (0..20)
+            .map(|index| {
+                let x = ((index * 7) % 20) as f64 * 5.0;
+                line(&[(x, 10.0), (x + 2.0, 12.0)])
+            })
+            .collect::<Vec<_>>();
+        let plot = Plot {
+            lines: reorder(lines.clone(), 0.1),
+            unoptimized_travel: travel(&lines),
+        };
+        assert!(plot.travel_distance() <= plot.unoptimized_travel);
+        assert!(plot.travel_distance() < plot.unoptimized_travel / 2.0);
+        assert_eq!(plot.lines.len(), 20);
+        assert!((plot.draw_distance() - 20.0 * 8.0_f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn dashes_split_at_segment_boundaries() {
+        let corner = line(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        assert_eq!(
+            dash(&corner, &[4.0, 2.0]),
+            vec![
+                line(&[(0.0, 0.0), (4.0, 0.0)]),
+                line(&[(6.0, 0.0), (10.0, 0.0)]),
+                line(&[(10.0, 2.0), (10.0, 6.0)]),
+                line(&[(10.0, 8.0), (10.0, 10.0)]),
+            ]
+        );
+        // A dash running over a corner keeps the corner
+        assert_eq!(
+            dash(&corner, &[12.0, 100.0]),
+            vec![line(&[(0.0, 0.0), (10.0, 0.0), (10.0, 2.0)])]
+        );
+    }
+
+    #[test]
+    fn dashes_finer_than_a_pen_are_drawn_solid() {
+        let line = crate::shapes::Shapes::Line(piet::kurbo::Line::new((0.0, 0.0), (1000.0, 0.0)));
+        let dashed = |dash: Vec<f64>| {
+            let style = crate::style::Style {
+                dash,
+                ..Default::default()
+            };
+            let drawing = Drawing::new(
+                piet::kurbo::Size::new(1000.0, 1000.0),
+                vec![line.with_style(style)],
+            );
+            Plot::new(&drawing, &PlotOptions::default()).lines.len()
+        };
+        assert_eq!(dashed(vec![150.0, 60.0]), 5);
+        assert_eq!(dashed(vec![0.000001, 0.000001]), 1);
+    }
+
+    #[test]
+    fn report_sums_up_both_distances() {
+        let plot = Plot {
+            lines: vec![line(&[(0.0, 0.0), (3.0, 4.0)])],
+            unoptimized_travel: 20.0,
+        };
+        assert_eq!(plot.draw_distance(), 5.0);
+        assert_eq!(plot.travel_distance(), 5.0);
+        assert_eq!(
+            plot.report(),
+            "1 line, drawing 5.0 mm and travelling 5.0 mm (was 20.0 mm)"
+        );
+    }
+}
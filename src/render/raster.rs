@@ -0,0 +1,151 @@
+use anyhow::{anyhow, Context};
+use piet::kurbo::{BezPath, PathEl};
+
+use super::Drawing;
+use crate::{
+    color::Color,
+    style::{LineCap, LineJoin, Style},
+};
+
+/// Largest image rasterized, in pixels. With four bytes per pixel
+/// it takes up to 256 MiB, before it is even encoded
+pub const MAX_PIXELS: f64 = 64.0 * 1024.0 * 1024.0;
+
+/// How a drawing is turned into pixels
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct RasterOptions {
+    /// Pixels per unit of the canvas size
+    pub scale: f64,
+    /// Fills the image before drawing, `None` keeps it transparent
+    pub background: Option<Color>,
+}
+
+impl Default for RasterOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            background: Some(Color::WHITE),
+        }
+    }
+}
+
+impl Drawing {
+    /// Rasterizes the drawing on the cpu, and encodes it as png.
+    /// Fails for images of more than [`MAX_PIXELS`]
+    pub fn to_png(&self, options: &RasterOptions) -> anyhow::Result<Vec<u8>> {
+        let width = (self.size.width * options.scale).round();
+        let height = (self.size.height * options.scale).round();
+        let pixels = width * height;
+        if pixels.is_nan() || pixels > MAX_PIXELS {
+            return Err(anyhow!(
+                "An image of {width}x{height} pixels is larger than the maximum of {} megapixels, \
+                 lower the scale",
+                MAX_PIXELS / (1024.0 * 1024.0)
+            ));
+        }
+        let (width, height) = (width as u32, height as u32);
+        let mut pixmap = tiny_skia::Pixmap::new(width, height).ok_or_else(|| {
+            anyhow!("Can't rasterize an image of {width}x{height} pixels, check the scale")
+        })?;
+        if let Some(background) = options.background {
+            pixmap.fill(to_skia(background));
+        }
+
+        let transform =
+            tiny_skia::Transform::from_scale(options.scale as f32, options.scale as f32);
+        // Curves are flattened by tiny-skia itself, so the path can be exact
+        for (path, style) in self.paths(0.1) {
+            let Some(path) = to_skia_path(&path) else {
+                continue;
+            };
+            let mut paint = tiny_skia::Paint {
+                anti_alias: true,
+                ..Default::default()
+            };
+            if let Some(fill) = style.fill_color() {
+                paint.set_color(to_skia(fill));
+                pixmap.fill_path(&path, &paint, tiny_skia::FillRule::Winding, transform, None);
+            }
+            if let Some(stroke) = style.stroke_color() {
+                paint.set_color(to_skia(stroke));
+                pixmap.stroke_path(&path, &paint, &to_skia_stroke(style), transform, None);
+            }
+        }
+
+        pixmap.encode_png().context("Could not encode the png")
+    }
+}
+
+fn to_skia(color: Color) -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba(
+        color.r.clamp(0.0, 1.0) as f32,
+        color.g.clamp(0.0, 1.0) as f32,
+        color.b.clamp(0.0, 1.0) as f32,
+        color.a.clamp(0.0, 1.0) as f32,
+    )
+    .expect("Channels are clamped")
+}
+
+fn to_skia_stroke(style: &Style) -> tiny_skia::Stroke {
+    tiny_skia::Stroke {
+        width: style.width as f32,
+        miter_limit: LineJoin::MITER_LIMIT as f32,
+        line_cap: match style.cap {
+            LineCap::Butt => tiny_skia::LineCap::Butt,
+            LineCap::Round => tiny_skia::LineCap::Round,
+            LineCap::Square => tiny_skia::LineCap::Square,
+        },
+        line_join: match style.join {
+            LineJoin::Miter => tiny_skia::LineJoin::Miter,
+            LineJoin::Round => tiny_skia::LineJoin::Round,
+            LineJoin::Bevel => tiny_skia::LineJoin::Bevel,
+        },
+        dash: style.dashes().and_then(|dashes| {
+            tiny_skia::StrokeDash::new(dashes.into_iter().map(|d| d as f32).collect(), 0.0)
+        }),
+    }
+}
+
+/// `None` for paths without any segment, which tiny-skia can't represent
+fn to_skia_path(path: &BezPath) -> Option<tiny_skia::Path> {
+    let mut builder = tiny_skia::PathBuilder::new();
+    for element in path.elements() {
+        match *element {
+            PathEl::MoveTo(p) => builder.move_to(p.x as f32, p.y as f32),
+            PathEl::LineTo(p) => builder.line_to(p.x as f32, p.y as f32),
+            PathEl::QuadTo(p1, p2) => {
+                builder.quad_to(p1.x as f32, p1.y as f32, p2.x as f32, p2.y as f32)
+            }
+            PathEl::CurveTo(p1, p2, p3) => builder.cubic_to(
+                p1.x as f32,
+                p1.y as f32,
+                p2.x as f32,
+                p2.y as f32,
+                p3.x as f32,
+                p3.y as f32,
+            ),
+            PathEl::ClosePath => builder.close(),
+        }
+    }
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huge_images_are_refused() {
+        let drawing = Drawing::new(piet::kurbo::Size::new(100.0, 50.0), Vec::new());
+        let png = |scale| {
+            drawing.to_png(&RasterOptions {
+                scale,
+                ..Default::default()
+            })
+        };
+        assert!(png(2.0).is_ok());
+        let error = png(1000.0).unwrap_err().to_string();
+        assert!(error.contains("100000x50000 pixels"), "{error}");
+        assert!(png(f64::NAN).is_err());
+    }
+}
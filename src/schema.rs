@@ -0,0 +1,507 @@
+use serde_json::{json, Map, Value as Json};
+
+use crate::{interface::Interface, value::ValueType};
+
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Pattern of the hex strings colors are read from
+const COLOR_PATTERN: &str = "^#?([0-9a-fA-F]{6}|[0-9a-fA-F]{8})$";
+
+/// Schema of the plain json a value of the given type is read from,
+/// see [`crate::value::Value::from_json`]
+pub fn type_schema(kind: ValueType) -> Json {
+    match kind {
+        ValueType::Number => json!({ "type": "number" }),
+        ValueType::Bool => json!({ "type": "boolean" }),
+        ValueType::Text => json!({ "type": "string" }),
+        ValueType::Point => json!({
+            "type": "object",
+            "properties": {
+                "x": { "type": "number" },
+                "y": { "type": "number" },
+            },
+            "required": ["x", "y"],
+            "additionalProperties": false,
+        }),
+        ValueType::Color => json!({
+            "type": "string",
+            "pattern": COLOR_PATTERN,
+        }),
+        ValueType::Any
+        | ValueType::Shape
+        | ValueType::Curve
+        | ValueType::Transform
+        | ValueType::Drawing => json!({}),
+    }
+}
+
+/// Schema of the json object setting the parameters of a graph.
+/// All parameters are optional, as they fall back to their default
+pub fn parameters_schema(name: &str, interface: &Interface) -> Json {
+    let properties = interface
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let mut schema = type_schema(parameter.kind);
+            let object = schema.as_object_mut().expect("Type schemas are objects");
+            object.insert("default".to_string(), parameter.default.to_json());
+            if let Some(min) = parameter.min {
+                object.insert("minimum".to_string(), json!(min));
+            }
+            if let Some(max) = parameter.max {
+                object.insert("maximum".to_string(), json!(max));
+            }
+            if !parameter.description.is_empty() {
+                object.insert("description".to_string(), json!(parameter.description));
+            }
+            (parameter.name.clone(), schema)
+        })
+        .collect::<Map<_, _>>();
+
+    json!({
+        "$schema": DIALECT,
+        "title": name,
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+    })
+}
+
+/// OpenAPI document of the endpoints served for the given graphs
+pub fn openapi(graphs: &[(&str, &Interface)]) -> Json {
+    let mut paths = Map::new();
+    paths.insert(
+        "/graphs".to_string(),
+        json!({
+            "get": {
+                "summary": "Names of all served graphs",
+                "responses": {
+                    "200": {
+                        "description": "The graph names",
+                        "content": { "application/json": { "schema": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                        } } },
+                    },
+                },
+            },
+        }),
+    );
+
+    for (name, interface) in graphs {
+        let parameters = parameters_schema(name, interface);
+        let outputs = interface
+            .outputs
+            .iter()
+            .map(|output| {
+                let mut schema = json!({});
+                if !output.description.is_empty() {
+                    schema["description"] = json!(output.description);
+                }
+                (output.name.clone(), schema)
+            })
+            .collect::<Map<_, _>>();
+
+        paths.insert(
+            format!("/graphs/{name}"),
+            json!({
+                "get": {
+                    "summary": format!("JSON Schema of the parameters of {name}"),
+                    "responses": {
+                        "200": {
+                            "description": "The schema of the solve request body",
+                            "content": { "application/json": { "schema": {} } },
+                        },
+                    },
+                },
+            }),
+        );
+        paths.insert(
+            format!("/graphs/{name}/interface"),
+            json!({
+                "get": {
+                    "summary": format!("Parameters and outputs of {name}"),
+                    "responses": {
+                        "200": {
+                            "description": "The interface of the graph",
+                            "content": { "application/json": { "schema": {
+                                "$ref": "#/components/schemas/Interface",
+                            } } },
+                        },
+                    },
+                },
+            }),
+        );
+        paths.insert(
+            format!("/graphs/{name}/solve"),
+            json!({
+                "post": {
+                    "summary": format!("Solves {name} with the given parameters"),
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": parameters } },
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "The outputs and drawings of the graph",
+                            "content": { "application/json": { "schema": {
+                                "type": "object",
+                                "properties": {
+                                    "outputs": {
+                                        "type": "object",
+                                        "properties": outputs,
+                                    },
+                                    "drawings": {
+                                        "type": "array",
+                                        "items": { "$ref": "#/components/schemas/Drawing" },
+                                    },
+                                    "errors": {
+                                        "type": "array",
+                                        "items": { "$ref": "#/components/schemas/NodeError" },
+                                    },
+                                },
+                            } } },
+                        },
+                        "400": { "$ref": "#/components/responses/Error" },
+                        "422": {
+                            "description": "The parameters don't match the schema",
+                            "content": { "application/json": { "schema": {
+                                "$ref": "#/components/schemas/ValidationError",
+                            } } },
+                        },
+                        "500": { "$ref": "#/components/responses/Error" },
+                    },
+                },
+            }),
+        );
+    }
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "recoded",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": {
+            "schemas": {
+                "Interface": {
+                    "type": "object",
+                    "properties": {
+                        "parameters": { "type": "array", "items": { "type": "object" } },
+                        "outputs": { "type": "array", "items": { "type": "object" } },
+                    },
+                },
+                "Drawing": {
+                    "type": "object",
+                    "properties": {
+                        "node": { "type": "integer" },
+                        "svg": { "type": "string" },
+                    },
+                },
+                "NodeError": {
+                    "type": "object",
+                    "properties": {
+                        "node": { "type": "integer" },
+                        "title": { "type": "string" },
+                        "message": { "type": "string" },
+                    },
+                },
+                "Error": {
+                    "type": "object",
+                    "properties": { "error": { "type": "string" } },
+                },
+                "ValidationError": {
+                    "type": "object",
+                    "properties": {
+                        "error": { "type": "string" },
+                        "fields": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "field": { "type": "string" },
+                                    "message": { "type": "string" },
+                                },
+                            },
+                        },
+                    },
+                },
+            },
+            "responses": {
+                "Error": {
+                    "description": "The request could not be answered",
+                    "content": { "application/json": { "schema": {
+                        "$ref": "#/components/schemas/Error",
+                    } } },
+                },
+            },
+        },
+    })
+}
+
+/// A part of a json document not matching its schema
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct FieldError {
+    /// JSON Pointer to the offending value, empty for the whole document
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    /// An error about a field of the top level object
+    pub fn property(name: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: property_pointer("", name),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.field.as_str() {
+            "" => write!(f, "{}", self.message),
+            field => write!(f, "{field}: {}", self.message),
+        }
+    }
+}
+
+/// Checks a json document against a schema, returning every mismatch.
+///
+/// Only the keywords written by this module are supported, which are
+/// `type`, `properties`, `required`, `additionalProperties`, `minimum`,
+/// `maximum` and the `pattern` of colors, other patterns are ignored
+pub fn validate(schema: &Json, value: &Json) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+    validate_at(schema, value, "", &mut errors);
+    errors
+}
+
+fn validate_at(schema: &Json, value: &Json, pointer: &str, errors: &mut Vec<FieldError>) {
+    let mut error = |message: String| {
+        errors.push(FieldError {
+            field: pointer.to_string(),
+            message,
+        })
+    };
+
+    if let Some(expected) = schema.get("type").and_then(Json::as_str) {
+        let matches = match expected {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "number" => value.is_number(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "boolean" => value.is_boolean(),
+            "null" => value.is_null(),
+            _ => true,
+        };
+        if !matches {
+            error(format!(
+                "expected {}, found {}",
+                article(expected),
+                json_type(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Json::as_f64) {
+            if number < min {
+                error(format!("{number} is less than the minimum of {min}"));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(Json::as_f64) {
+            if number > max {
+                error(format!("{number} is greater than the maximum of {max}"));
+            }
+        }
+    }
+
+    let pattern = schema.get("pattern").and_then(Json::as_str);
+    if let (Some(text), Some(COLOR_PATTERN)) = (value.as_str(), pattern) {
+        if crate::color::Color::from_hex(text).is_none() {
+            error(format!("'{text}' is not a hex color like #ff8800"));
+        }
+    }
+
+    let Some(object) = value.as_object() else {
+        return;
+    };
+    let properties = schema.get("properties").and_then(Json::as_object);
+    for required in schema
+        .get("required")
+        .and_then(Json::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Json::as_str)
+    {
+        if !object.contains_key(required) {
+            errors.push(FieldError {
+                field: pointer.to_string(),
+                message: format!("missing the required field '{required}'"),
+            });
+        }
+    }
+
+    let mut keys = object.keys().collect::<Vec<_>>();
+    keys.sort();
+    for key in keys {
+        let field = property_pointer(pointer, key);
+        match properties.and_then(|properties| properties.get(key)) {
+            Some(property) => validate_at(property, &object[key], &field, errors),
+            None if schema.get("additionalProperties") == Some(&Json::Bool(false)) => {
+                let known = properties
+                    .map(|properties| {
+                        let names = properties.keys().cloned().collect::<Vec<_>>();
+                        names.join(", ")
+                    })
+                    .unwrap_or_default();
+                errors.push(FieldError {
+                    field,
+                    message: if known.is_empty() {
+                        "unknown field, there are no fields".to_string()
+                    } else {
+                        format!("unknown field, expected one of {known}")
+                    },
+                });
+            }
+            None => {}
+        }
+    }
+}
+
+/// Appends a property to a JSON Pointer, escaping it as needed
+fn property_pointer(pointer: &str, name: &str) -> String {
+    format!("{pointer}/{}", name.replace('~', "~0").replace('/', "~1"))
+}
+
+fn json_type(value: &Json) -> &'static str {
+    match value {
+        Json::Null => "null",
+        Json::Bool(_) => "a boolean",
+        Json::Number(_) => "a number",
+        Json::String(_) => "a string",
+        Json::Array(_) => "an array",
+        Json::Object(_) => "an object",
+    }
+}
+
+fn article(kind: &str) -> String {
+    match kind {
+        "null" => kind.to_string(),
+        "array" | "integer" | "object" => format!("an {kind}"),
+        _ => format!("a {kind}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point_schema() -> Json {
+        json!({
+            "type": "object",
+            "properties": {
+                "center": type_schema(ValueType::Point),
+                "radius": { "type": "number", "minimum": 1.0, "maximum": 50.0 },
+                "fill": type_schema(ValueType::Color),
+            },
+            "additionalProperties": false,
+        })
+    }
+
+    fn messages(schema: &Json, value: Json) -> Vec<String> {
+        validate(schema, &value)
+            .iter()
+            .map(FieldError::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn valid_documents_have_no_errors() {
+        let value = json!({ "center": { "x": 1, "y": 2.5 }, "radius": 5, "fill": "#ff8800" });
+        assert_eq!(validate(&point_schema(), &value), vec![]);
+    }
+
+    #[test]
+    fn type_mismatch_names_both_types() {
+        assert_eq!(
+            messages(&point_schema(), json!({ "radius": "big" })),
+            vec!["/radius: expected a number, found a string"]
+        );
+        assert_eq!(
+            messages(&point_schema(), json!([])),
+            vec!["expected an object, found an array"]
+        );
+    }
+
+    #[test]
+    fn numbers_outside_the_bounds() {
+        assert_eq!(
+            messages(&point_schema(), json!({ "radius": 0.5 })),
+            vec!["/radius: 0.5 is less than the minimum of 1"]
+        );
+        assert_eq!(
+            messages(&point_schema(), json!({ "radius": 60 })),
+            vec!["/radius: 60 is greater than the maximum of 50"]
+        );
+    }
+
+    #[test]
+    fn missing_required_fields_point_at_their_object() {
+        assert_eq!(
+            messages(&point_schema(), json!({ "center": { "x": 1 } })),
+            vec!["/center: missing the required field 'y'"]
+        );
+    }
+
+    #[test]
+    fn unknown_fields_list_the_known_ones() {
+        assert_eq!(
+            messages(&point_schema(), json!({ "radiu": 5 })),
+            vec!["/radiu: unknown field, expected one of center, fill, radius"]
+        );
+        assert_eq!(
+            messages(
+                &point_schema(),
+                json!({ "center": { "x": 1, "y": 2, "z": 3 } })
+            ),
+            vec!["/center/z: unknown field, expected one of x, y"]
+        );
+        let empty = json!({ "type": "object", "properties": {}, "additionalProperties": false });
+        assert_eq!(
+            messages(&empty, json!({ "radius": 5 })),
+            vec!["/radius: unknown field, there are no fields"]
+        );
+    }
+
+    #[test]
+    fn pointers_escape_tilde_and_slash() {
+        assert_eq!(property_pointer("", "a/b~c"), "/a~1b~0c");
+        assert_eq!(property_pointer("/outer", "~/"), "/outer/~0~1");
+        assert_eq!(FieldError::property("x/y", "bad").field, "/x~1y");
+    }
+
+    #[test]
+    fn colors_are_hex_strings() {
+        for color in ["#ff8800", "ff8800", "#FF880080"] {
+            assert_eq!(
+                messages(&point_schema(), json!({ "fill": color })),
+                Vec::<String>::new()
+            );
+        }
+        assert_eq!(
+            messages(&point_schema(), json!({ "fill": "orange" })),
+            vec!["/fill: 'orange' is not a hex color like #ff8800"]
+        );
+    }
+
+    #[test]
+    fn other_patterns_are_not_colors() {
+        let schema = json!({ "type": "string", "pattern": "^[a-z]+$" });
+        assert_eq!(validate(&schema, &json!("orange")), vec![]);
+    }
+}
@@ -0,0 +1,414 @@
+use std::{
+    collections::HashMap,
+    panic::AssertUnwindSafe,
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use anyhow::{anyhow, Context};
+use axum::{
+    extract::{rejection::JsonRejection, Path as UrlPath, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use egui_snarl::Snarl;
+use serde_json::json;
+
+use crate::{
+    cli::ServeArgs,
+    document::{self, Document},
+    interface::{self, Interface},
+    nodes::Nodes,
+    render,
+    schema::{self, FieldError},
+    solver::Solver,
+    value::Value,
+};
+
+/// A sketch served over http, solved again for every request
+struct Graph {
+    snarl: Snarl<Nodes>,
+    /// The interface as it was saved, its defaults are used
+    /// for every parameter missing from a request
+    interface: Interface,
+    /// JSON Schema of the solve request body
+    schema: serde_json::Value,
+    /// Kept between requests, so only the parts of the
+    /// graph depending on changed parameters are solved again
+    solver: Solver,
+}
+
+impl Graph {
+    fn new(name: &str, snarl: Snarl<Nodes>) -> Self {
+        let interface = Interface::of(&snarl);
+        Graph {
+            schema: schema::parameters_schema(name, &interface),
+            interface,
+            snarl,
+            solver: Solver::default(),
+        }
+    }
+}
+
+struct Served {
+    graphs: HashMap<String, Mutex<Graph>>,
+    openapi: serde_json::Value,
+}
+
+type Shared = Arc<Served>;
+
+/// Reasons why a request could not be answered
+enum ApiError {
+    NotFound(String),
+    BadRequest(anyhow::Error),
+    /// The request body does not match the schema of the graph
+    Invalid(Vec<FieldError>),
+    /// Solving failed in a way no request should be able to cause
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::NotFound(name) => {
+                (StatusCode::NOT_FOUND, format!("There is no graph '{name}'"))
+            }
+            ApiError::BadRequest(error) => (StatusCode::BAD_REQUEST, format!("{error:#}")),
+            ApiError::Internal(message) => (StatusCode::INTERNAL_SERVER_ERROR, message),
+            ApiError::Invalid(fields) => {
+                let message = fields
+                    .iter()
+                    .map(FieldError::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let body = json!({
+                    "error": format!("Invalid parameters: {message}"),
+                    "fields": fields,
+                });
+                return (StatusCode::UNPROCESSABLE_ENTITY, Json(body)).into_response();
+            }
+        };
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+}
+
+/// Serves every sketch in a directory until the process is stopped
+pub fn serve(args: &ServeArgs) -> anyhow::Result<()> {
+    let graphs = load_graphs(&args.dir)?;
+    let mut names = graphs.keys().collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        println!("Serving /graphs/{name}");
+    }
+    let app = router(graphs);
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(args.address)
+            .await
+            .with_context(|| format!("Could not listen on {}", args.address))?;
+        println!("Listening on http://{}", args.address);
+        axum::serve(listener, app).await?;
+        Ok(())
+    })
+}
+
+/// The endpoints of all graphs, and the OpenAPI document describing them
+fn router(mut graphs: HashMap<String, Mutex<Graph>>) -> Router {
+    let mut interfaces = graphs
+        .iter_mut()
+        .map(|(name, graph)| {
+            let graph = graph.get_mut().expect("The graph was just loaded");
+            (name.as_str(), &graph.interface)
+        })
+        .collect::<Vec<_>>();
+    interfaces.sort_by_key(|(name, _)| *name);
+    let openapi = schema::openapi(&interfaces);
+
+    Router::new()
+        .route("/openapi.json", get(openapi_document))
+        .route("/graphs", get(list_graphs))
+        .route("/graphs/{name}", get(graph_schema))
+        .route("/graphs/{name}/interface", get(graph_interface))
+        .route("/graphs/{name}/solve", post(solve_graph))
+        .with_state(Arc::new(Served { graphs, openapi }))
+}
+
+/// Loads all sketches in the directory, named after their file
+fn load_graphs(dir: &Path) -> anyhow::Result<HashMap<String, Mutex<Graph>>> {
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("Could not read {}", dir.display()))?;
+    let mut graphs = HashMap::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some(document::EXTENSION) {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let document =
+            Document::load(&path).with_context(|| format!("Could not load {}", path.display()))?;
+        graphs.insert(
+            name.to_string(),
+            Mutex::new(Graph::new(name, document.snarl)),
+        );
+    }
+    if graphs.is_empty() {
+        anyhow::bail!("There are no sketches in {}", dir.display());
+    }
+    Ok(graphs)
+}
+
+async fn openapi_document(State(served): State<Shared>) -> Json<serde_json::Value> {
+    Json(served.openapi.clone())
+}
+
+async fn list_graphs(State(served): State<Shared>) -> Json<Vec<String>> {
+    let mut names = served.graphs.keys().cloned().collect::<Vec<_>>();
+    names.sort();
+    Json(names)
+}
+
+async fn graph_schema(
+    State(served): State<Shared>,
+    UrlPath(name): UrlPath<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let graph = served.graphs.get(&name).ok_or(ApiError::NotFound(name))?;
+    let graph = lock(graph);
+    Ok(Json(graph.schema.clone()))
+}
+
+async fn graph_interface(
+    State(served): State<Shared>,
+    UrlPath(name): UrlPath<String>,
+) -> Result<Json<Interface>, ApiError> {
+    let graph = served.graphs.get(&name).ok_or(ApiError::NotFound(name))?;
+    let graph = lock(graph);
+    Ok(Json(graph.interface.clone()))
+}
+
+/// Locks a graph even if a panic while solving poisoned it, which
+/// is recovered from by [`solve_graph`] before the lock is released
+fn lock(graph: &Mutex<Graph>) -> MutexGuard<'_, Graph> {
+    graph.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Solves a graph with the parameters in the request body, answering
+/// with the value of every output and the drawing of every canvas.
+/// The body is checked against the schema of the graph first, so
+/// consumers learn about every invalid field at once
+async fn solve_graph(
+    State(served): State<Shared>,
+    UrlPath(name): UrlPath<String>,
+    body: Result<Json<serde_json::Value>, JsonRejection>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if !served.graphs.contains_key(&name) {
+        return Err(ApiError::NotFound(name));
+    }
+    let Json(body) =
+        body.map_err(|rejection| ApiError::BadRequest(anyhow!(rejection.body_text())))?;
+    // Solving is CPU bound, so it must not hold up the async workers
+    tokio::task::spawn_blocking(move || {
+        let mut graph = lock(&served.graphs[&name]);
+        match std::panic::catch_unwind(AssertUnwindSafe(|| solve(&mut graph, &body))) {
+            Ok(response) => response,
+            Err(_) => {
+                // The cached results may be half updated
+                graph.solver = Solver::default();
+                Err(ApiError::Internal(format!(
+                    "Solving the graph '{name}' failed unexpectedly"
+                )))
+            }
+        }
+    })
+    .await
+    .map_err(|error| ApiError::Internal(error.to_string()))?
+}
+
+fn solve(graph: &mut Graph, body: &serde_json::Value) -> Result<Json<serde_json::Value>, ApiError> {
+    let Graph {
+        snarl,
+        interface,
+        schema,
+        solver,
+    } = graph;
+
+    let errors = schema::validate(schema, body);
+    if !errors.is_empty() {
+        return Err(ApiError::Invalid(errors));
+    }
+
+    // Parameters missing from the request are reset to their default,
+    // the schema already made sure all others have the right type
+    interface::set_parameters(snarl, &interface.defaults()).map_err(ApiError::BadRequest)?;
+    let mut errors = Vec::new();
+    for (name, json) in body.as_object().into_iter().flatten() {
+        let result = Value::from_json(json)
+            .map_err(anyhow::Error::from)
+            .and_then(|value| {
+                let parameter = HashMap::from([(name.clone(), value)]);
+                interface::set_parameters(snarl, &parameter)
+            });
+        if let Err(error) = result {
+            errors.push(FieldError::property(name, error.root_cause().to_string()));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::Invalid(errors));
+    }
+
+    for parameter in &interface.parameters {
+        solver.edited(snarl, parameter.node);
+    }
+    let results = solver.solve(snarl);
+    let outputs = interface
+        .output_values(results)
+        .map(|(output, value)| (output.name.clone(), value.map(Value::to_json).into()))
+        .collect::<serde_json::Map<_, _>>();
+    let drawings = render::canvas_drawings(snarl, results)
+        .into_iter()
+        .flat_map(|(node, drawings)| {
+            drawings.into_iter().map(move |drawing| {
+                json!({
+                    "node": node.0,
+                    "svg": String::from_utf8_lossy(&drawing.to_svg()),
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut errors = results
+        .errors()
+        .map(|(node, error)| (node, error.to_string()))
+        .collect::<Vec<_>>();
+    errors.sort();
+    let errors = errors
+        .into_iter()
+        .map(|(node, message)| {
+            json!({
+                "node": node.0,
+                "title": snarl[node].title(),
+                "message": message,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(json!({
+        "outputs": outputs,
+        "drawings": drawings,
+        "errors": errors,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::Request};
+    use egui_snarl::{InPinId, OutPinId};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{
+        nodes::parameter::{GraphOutputNode, ParameterNode},
+        value::ValueType,
+    };
+
+    /// Serves a graph "triple", which multiplies its "count" by three
+    fn app() -> Router {
+        let mut snarl = Snarl::new();
+        let count = snarl.insert_node(
+            egui::Pos2::ZERO,
+            Nodes::Parameter(ParameterNode {
+                name: "count".to_string(),
+                kind: ValueType::Number,
+                default: Value::Number(2.0),
+                ..Default::default()
+            }),
+        );
+        let product = snarl.insert_node(
+            egui::Pos2::ZERO,
+            serde_json::from_value(json!({
+                "Math": { "operation": "Multiply", "a": 0.0, "b": 3.0 }
+            }))
+            .unwrap(),
+        );
+        let output = snarl.insert_node(
+            egui::Pos2::ZERO,
+            Nodes::Output(GraphOutputNode {
+                name: "tripled".to_string(),
+                description: String::new(),
+            }),
+        );
+        for (from, to) in [(count, product), (product, output)] {
+            snarl.connect(
+                OutPinId {
+                    node: from,
+                    output: 0,
+                },
+                InPinId { node: to, input: 0 },
+            );
+        }
+        let graphs = HashMap::from([(
+            "triple".to_string(),
+            Mutex::new(Graph::new("triple", snarl)),
+        )]);
+        router(graphs)
+    }
+
+    async fn send(request: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let response = app().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn solve(body: serde_json::Value) -> Request<Body> {
+        Request::post("/graphs/triple/solve")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn unknown_graphs_are_not_found() {
+        let request = Request::get("/graphs/missing").body(Body::empty()).unwrap();
+        let (status, body) = send(request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "There is no graph 'missing'");
+
+        let request = Request::post("/graphs/missing/solve")
+            .header("content-type", "application/json")
+            .body(Body::from("{}"))
+            .unwrap();
+        assert_eq!(send(request).await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn graphs_serve_their_parameter_schema() {
+        let request = Request::get("/graphs/triple").body(Body::empty()).unwrap();
+        let (status, body) = send(request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["properties"]["count"]["type"], "number");
+    }
+
+    #[tokio::test]
+    async fn invalid_parameters_are_unprocessable() {
+        let (status, body) = send(solve(json!({ "count": "three", "other": 1 }))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["fields"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn graphs_are_solved_with_the_posted_parameters() {
+        let (status, body) = send(solve(json!({ "count": 4 }))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["outputs"]["tripled"], 12.0);
+        assert_eq!(body["errors"], json!([]));
+
+        // Missing parameters take their default
+        let (_, body) = send(solve(json!({}))).await;
+        assert_eq!(body["outputs"]["tripled"], 6.0);
+    }
+}
@@ -1,18 +1,257 @@
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+use piet::kurbo::{self, Affine, PathEl, Point, Rect, Shape};
+
+use crate::{
+    nodes::{format_float, format_point},
+    style::Style,
+};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
 pub enum Shapes {
-    Circle(piet::kurbo::Circle),
+    Circle(kurbo::Circle),
+    Rect(kurbo::Rect),
+    RoundedRect(kurbo::RoundedRect),
+    Ellipse(kurbo::Ellipse),
+    Line(kurbo::Line),
+    Arc(kurbo::Arc),
+    /// Any outline made of lines and curves, open or closed
+    Path(kurbo::BezPath),
+    /// A shape painted with a style of its own instead of the default one
+    Styled(Box<Shapes>, Style),
 }
 
 impl Default for Shapes {
     fn default() -> Self {
-        Self::Circle(piet::kurbo::Circle::default())
+        Self::Circle(kurbo::Circle::default())
     }
 }
 
+/// Expands to a match binding the kurbo shape inside any variant,
+/// styled shapes bind the shape they wrap
+macro_rules! each_shape {
+    ($shapes:expr, $shape:ident => $body:expr) => {
+        match $shapes {
+            Shapes::Circle($shape) => $body,
+            Shapes::Rect($shape) => $body,
+            Shapes::RoundedRect($shape) => $body,
+            Shapes::Ellipse($shape) => $body,
+            Shapes::Line($shape) => $body,
+            Shapes::Arc($shape) => $body,
+            Shapes::Path($shape) => $body,
+            Shapes::Styled($shape, _) => $body,
+        }
+    };
+}
+
+static DEFAULT_STYLE: Style = Style::DEFAULT;
+
 impl Shapes {
-    pub fn get_shape(&self) -> &impl piet::kurbo::Shape {
+    /// The shape without its style
+    pub fn geometry(&self) -> &Shapes {
+        match self {
+            Shapes::Styled(shape, _) => shape.geometry(),
+            shape => shape,
+        }
+    }
+
+    pub fn style(&self) -> &Style {
+        match self {
+            Shapes::Styled(_, style) => style,
+            _ => &DEFAULT_STYLE,
+        }
+    }
+
+    /// The same shape painted with the given style, replacing any previous one
+    pub fn with_style(&self, style: Style) -> Self {
+        Shapes::Styled(Box::new(self.geometry().clone()), style)
+    }
+
+    /// The center of round shapes and rectangles, the middle of lines
+    /// and the center of the bounding box of paths
+    pub fn center(&self) -> Point {
+        match self.geometry() {
+            Shapes::Circle(circle) => circle.center,
+            Shapes::Rect(rect) => rect.center(),
+            Shapes::RoundedRect(rect) => rect.rect().center(),
+            Shapes::Ellipse(ellipse) => ellipse.center(),
+            Shapes::Line(line) => line.p0.midpoint(line.p1),
+            Shapes::Arc(arc) => arc.center,
+            shape => shape.bounding_box().center(),
+        }
+    }
+
+    /// The shape moved by a transform. Shapes keep their variant as long
+    /// as the transform can map them onto one of the same kind, otherwise
+    /// they turn into ellipses or paths. The style is kept as it is,
+    /// so strokes don't grow with scaled shapes
+    pub fn transform(&self, affine: Affine) -> Shapes {
+        let similarity = Similarity::of(affine);
+        let keeps_axes = {
+            let [a, b, c, d, _, _] = affine.as_coeffs();
+            (b == 0.0 && c == 0.0) || (a == 0.0 && d == 0.0)
+        };
+        match self {
+            Shapes::Circle(circle) => match similarity {
+                Some(similarity) => Shapes::Circle(kurbo::Circle::new(
+                    affine * circle.center,
+                    circle.radius * similarity.scale,
+                )),
+                None => Shapes::Ellipse(affine * *circle),
+            },
+            Shapes::Rect(rect) if keeps_axes => Shapes::Rect(affine.transform_rect_bbox(*rect)),
+            Shapes::Rect(rect) => Shapes::Path(affine * rect.to_path(0.1)),
+            Shapes::RoundedRect(rect) => match (rect.radii().as_single_radius(), similarity) {
+                (Some(radius), Some(similarity)) if keeps_axes => {
+                    Shapes::RoundedRect(kurbo::RoundedRect::from_rect(
+                        affine.transform_rect_bbox(rect.rect()),
+                        radius * similarity.scale,
+                    ))
+                }
+                _ => Shapes::Path(affine * rect.to_path(0.1)),
+            },
+            Shapes::Ellipse(ellipse) => Shapes::Ellipse(affine * *ellipse),
+            Shapes::Line(line) => Shapes::Line(affine * *line),
+            Shapes::Arc(arc) => match similarity {
+                Some(similarity) if similarity.mirrored => Shapes::Arc(kurbo::Arc {
+                    center: affine * arc.center,
+                    radii: arc.radii * similarity.scale,
+                    start_angle: -arc.start_angle,
+                    sweep_angle: -arc.sweep_angle,
+                    x_rotation: similarity.rotation - arc.x_rotation,
+                }),
+                Some(_) => Shapes::Arc(affine * *arc),
+                // Angles of arcs don't survive shearing, their outline does
+                None => Shapes::Path(affine * arc.to_path(0.1)),
+            },
+            Shapes::Path(path) => Shapes::Path(affine * path.clone()),
+            Shapes::Styled(shape, style) => {
+                Shapes::Styled(Box::new(shape.transform(affine)), style.clone())
+            }
+        }
+    }
+}
+
+/// A transform which keeps angles, made of a uniform scale,
+/// a rotation and possibly a reflection, plus a translation
+#[derive(Clone, Copy)]
+struct Similarity {
+    scale: f64,
+    rotation: f64,
+    mirrored: bool,
+}
+
+impl Similarity {
+    fn of(affine: Affine) -> Option<Self> {
+        let [a, b, c, d, _, _] = affine.as_coeffs();
+        let scale = a.hypot(b);
+        let tolerance = 1e-12 * scale.max(1.0);
+        if scale == 0.0
+            || (c.hypot(d) - scale).abs() > tolerance
+            || (a * c + b * d).abs() > tolerance
+        {
+            return None;
+        }
+        Some(Self {
+            scale,
+            rotation: b.atan2(a),
+            mirrored: affine.determinant() < 0.0,
+        })
+    }
+}
+
+/// Lets every renderer draw any variant, while keeping
+/// the shortcuts piet takes for lines, rectangles and circles
+impl Shape for Shapes {
+    type PathElementsIter<'iter> = std::vec::IntoIter<PathEl>;
+
+    fn path_elements(&self, tolerance: f64) -> Self::PathElementsIter<'_> {
+        each_shape!(self, shape => shape.path_elements(tolerance).collect::<Vec<_>>().into_iter())
+    }
+
+    fn area(&self) -> f64 {
+        each_shape!(self, shape => shape.area())
+    }
+
+    fn perimeter(&self, accuracy: f64) -> f64 {
+        each_shape!(self, shape => shape.perimeter(accuracy))
+    }
+
+    fn winding(&self, pt: Point) -> i32 {
+        each_shape!(self, shape => shape.winding(pt))
+    }
+
+    fn bounding_box(&self) -> Rect {
+        each_shape!(self, shape => shape.bounding_box())
+    }
+
+    fn as_line(&self) -> Option<kurbo::Line> {
+        each_shape!(self, shape => shape.as_line())
+    }
+
+    fn as_rect(&self) -> Option<Rect> {
+        each_shape!(self, shape => shape.as_rect())
+    }
+
+    fn as_rounded_rect(&self) -> Option<kurbo::RoundedRect> {
+        each_shape!(self, shape => shape.as_rounded_rect())
+    }
+
+    fn as_circle(&self) -> Option<kurbo::Circle> {
+        each_shape!(self, shape => shape.as_circle())
+    }
+
+    fn as_path_slice(&self) -> Option<&[PathEl]> {
+        each_shape!(self, shape => shape.as_path_slice())
+    }
+}
+
+impl std::fmt::Display for Shapes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Shapes::Circle(shape) => shape,
+            Shapes::Circle(circle) => write!(
+                f,
+                "Circle {} r={}",
+                format_point(circle.center),
+                format_float(circle.radius)
+            ),
+            Shapes::Rect(rect) => write!(
+                f,
+                "Rectangle {} {}x{}",
+                format_point(rect.origin()),
+                format_float(rect.width()),
+                format_float(rect.height())
+            ),
+            Shapes::RoundedRect(rect) => write!(
+                f,
+                "Rounded Rectangle {} {}x{}",
+                format_point(rect.origin()),
+                format_float(rect.width()),
+                format_float(rect.height())
+            ),
+            Shapes::Ellipse(ellipse) => {
+                let radii = ellipse.radii();
+                write!(
+                    f,
+                    "Ellipse {} r={}/{}",
+                    format_point(ellipse.center()),
+                    format_float(radii.x),
+                    format_float(radii.y)
+                )
+            }
+            Shapes::Line(line) => write!(
+                f,
+                "Line {} {}",
+                format_point(line.p0),
+                format_point(line.p1)
+            ),
+            Shapes::Arc(arc) => write!(
+                f,
+                "Arc {} r={} sweep={}",
+                format_point(arc.center),
+                format_float(arc.radii.x),
+                format_float(arc.sweep_angle)
+            ),
+            Shapes::Path(path) => write!(f, "Path of {} elements", path.elements().len()),
+            Shapes::Styled(shape, _) => write!(f, "Styled {shape}"),
         }
     }
 }
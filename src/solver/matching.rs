@@ -0,0 +1,345 @@
+use crate::{
+    nodes::Nodes,
+    tree::{DataTree, Path},
+    value::Value,
+};
+
+use super::EvalError;
+
+/// How an input pin consumes the value arriving at it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// The node works on single items, lists are matched
+    /// and the node is evaluated once per item
+    Item,
+    /// The node works on the whole list at once
+    List,
+    /// The node works on the whole tree at once,
+    /// instead of once per branch
+    Tree,
+}
+
+/// Strategy to match up the items of multiple lists, when
+/// lists flow into more than one item input of a node
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListMatching {
+    /// Stops when the shortest list runs out of items
+    Shortest,
+    /// Continues until the longest list runs out of items,
+    /// repeating the last item of all shorter lists
+    #[default]
+    Longest,
+    /// Evaluates every combination of items
+    CrossReference,
+}
+
+impl ListMatching {
+    pub const ALL: [Self; 3] = [Self::Shortest, Self::Longest, Self::CrossReference];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Shortest => "Shortest list",
+            Self::Longest => "Longest list",
+            Self::CrossReference => "Cross reference",
+        }
+    }
+
+    /// How often a node is evaluated for lists of the given lengths,
+    /// `None` if there are too many combinations to even count them
+    fn count(&self, lengths: &[usize]) -> Option<usize> {
+        if lengths.contains(&0) {
+            return Some(0);
+        }
+        match self {
+            Self::Shortest => Some(lengths.iter().copied().min().unwrap_or_default()),
+            Self::Longest => Some(lengths.iter().copied().max().unwrap_or_default()),
+            Self::CrossReference => lengths
+                .iter()
+                .try_fold(1_usize, |count, length| count.checked_mul(*length)),
+        }
+    }
+
+    /// The item indices into each list, for every evaluation of the node.
+    /// Errors if the node would be evaluated more than [`Value::MAX_COUNT`]
+    /// times, as the results of all evaluations are kept
+    fn combinations(
+        self,
+        lengths: &[usize],
+    ) -> Result<impl Iterator<Item = Vec<usize>> + '_, EvalError> {
+        let count = self
+            .count(lengths)
+            .filter(|count| *count <= Value::MAX_COUNT)
+            .ok_or_else(|| {
+                EvalError::Domain(format!(
+                    "Matching lists of {} items would evaluate the node more than {} times",
+                    lengths
+                        .iter()
+                        .map(usize::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    Value::MAX_COUNT
+                ))
+            })?;
+        Ok((0..count).map(move |index| match self {
+            Self::Shortest => vec![index; lengths.len()],
+            Self::Longest => lengths.iter().map(|length| index.min(length - 1)).collect(),
+            Self::CrossReference => {
+                // The last list changes fastest, like nested loops
+                let mut rest = index;
+                let mut combination = lengths
+                    .iter()
+                    .rev()
+                    .map(|length| {
+                        let item = rest % length;
+                        rest /= length;
+                        item
+                    })
+                    .collect::<Vec<_>>();
+                combination.reverse();
+                combination
+            }
+        }))
+    }
+}
+
+/// Solves a node, evaluating it once per item if lists arrive at
+/// any of its item inputs. Every output then is a list holding the
+/// result of each evaluation, lists nested in lists are matched recursively.
+///
+/// Trees arriving at inputs which don't take whole trees are split
+/// up first, the node is then solved once per branch with the branch
+/// items as a list.
+pub(super) fn solve_matched(node: &Nodes, inputs: Vec<Value>) -> Result<Vec<Value>, EvalError> {
+    let trees = inputs
+        .iter()
+        .enumerate()
+        .filter_map(|(index, value)| match (node.input_access(index), value) {
+            (Access::Tree, _) => None,
+            (_, Value::Tree(tree)) => Some((index, tree)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if !trees.is_empty() {
+        return solve_branches(node, &inputs, &trees);
+    }
+
+    let lists = inputs
+        .iter()
+        .enumerate()
+        .filter_map(|(index, value)| match (node.input_access(index), value) {
+            (Access::Item, Value::List(items)) => Some((index, items)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if lists.is_empty() {
+        return node.solve(&inputs);
+    }
+
+    let lengths = lists
+        .iter()
+        .map(|(_, items)| items.len())
+        .collect::<Vec<_>>();
+    let mut outputs = vec![Vec::new(); node.outputs()];
+    for combination in node.matching().combinations(&lengths)? {
+        let mut scalar = inputs.clone();
+        for ((index, items), item) in lists.iter().zip(combination) {
+            scalar[*index] = items[item].clone();
+        }
+        for (output, value) in solve_matched(node, scalar)?.into_iter().enumerate() {
+            outputs[output].push(value);
+        }
+    }
+
+    Ok(outputs.into_iter().map(Value::List).collect())
+}
+
+/// Solves a node once per branch of the tree with the most branches.
+/// Branches of the other trees are matched by their index, repeating the
+/// last branch of smaller trees. The outputs are trees with the same paths
+fn solve_branches(
+    node: &Nodes,
+    inputs: &[Value],
+    trees: &[(usize, &DataTree)],
+) -> Result<Vec<Value>, EvalError> {
+    let (_, primary) = trees
+        .iter()
+        .rev()
+        .max_by_key(|(_, tree)| tree.branches().len())
+        .expect("There is at least one tree");
+
+    let mut outputs: Vec<Vec<(Path, Vec<Value>)>> = vec![Vec::new(); node.outputs()];
+    for (branch_index, branch) in primary.branches().iter().enumerate() {
+        let mut scalar = inputs.to_vec();
+        for (index, tree) in trees {
+            let branches = tree.branches();
+            scalar[*index] = match branches.get(branch_index.min(branches.len().saturating_sub(1)))
+            {
+                Some(branch) => Value::List(branch.items.clone()),
+                None => Value::List(Vec::new()),
+            };
+        }
+        for (output, value) in solve_matched(node, scalar)?.into_iter().enumerate() {
+            let items = match value {
+                Value::List(items) => items,
+                value => vec![value],
+            };
+            outputs[output].push((branch.path.clone(), items));
+        }
+    }
+
+    Ok(outputs
+        .into_iter()
+        .map(|branches| Value::Tree(DataTree::from_branches(branches)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::Color, nodes::color::GradientNode};
+
+    /// A strategy, the lengths of the lists and the expected combinations
+    type Case = (ListMatching, &'static [usize], Vec<Vec<usize>>);
+
+    fn combinations(matching: ListMatching, lengths: &[usize]) -> Vec<Vec<usize>> {
+        matching.combinations(lengths).unwrap().collect()
+    }
+
+    #[test]
+    fn lists_are_matched_by_each_strategy() {
+        use ListMatching::*;
+        let table: [Case; 9] = [
+            (Shortest, &[2, 3], vec![vec![0, 0], vec![1, 1]]),
+            (Longest, &[2, 3], vec![vec![0, 0], vec![1, 1], vec![1, 2]]),
+            (
+                CrossReference,
+                &[2, 3],
+                vec![
+                    vec![0, 0],
+                    vec![0, 1],
+                    vec![0, 2],
+                    vec![1, 0],
+                    vec![1, 1],
+                    vec![1, 2],
+                ],
+            ),
+            (
+                Longest,
+                &[1, 1, 3],
+                vec![vec![0, 0, 0], vec![0, 0, 1], vec![0, 0, 2]],
+            ),
+            (
+                CrossReference,
+                &[1, 2, 1],
+                vec![vec![0, 0, 0], vec![0, 1, 0]],
+            ),
+            // An empty list leaves nothing to evaluate
+            (Shortest, &[0, 3], vec![]),
+            (Longest, &[3, 0], vec![]),
+            (CrossReference, &[2, 0, 2], vec![]),
+            (Longest, &[], vec![]),
+        ];
+        for (matching, lengths, expected) in table {
+            assert_eq!(
+                combinations(matching, lengths),
+                expected,
+                "{matching:?} {lengths:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn too_many_combinations_are_an_error() {
+        let huge = [Value::MAX_COUNT, 2];
+        assert!(matches!(
+            ListMatching::CrossReference.combinations(&huge),
+            Err(EvalError::Domain(_))
+        ));
+        // Counting them would overflow
+        assert!(ListMatching::CrossReference
+            .combinations(&[usize::MAX, usize::MAX])
+            .is_err());
+        assert_eq!(
+            ListMatching::Longest.combinations(&huge).unwrap().count(),
+            Value::MAX_COUNT
+        );
+    }
+
+    #[test]
+    fn cross_referencing_the_largest_lists_is_refused() {
+        let node: Nodes = serde_json::from_value(serde_json::json!({
+            "Math": { "operation": "Add", "a": 0.0, "b": 0.0, "matching": "CrossReference" }
+        }))
+        .unwrap();
+        let list = Value::List(vec![Value::Number(1.0); Value::MAX_COUNT]);
+        assert!(matches!(
+            solve_matched(&node, vec![list.clone(), list]),
+            Err(EvalError::Domain(_))
+        ));
+    }
+
+    #[test]
+    fn trees_are_solved_per_branch() {
+        let node: Nodes = serde_json::from_value(serde_json::json!({
+            "Math": { "operation": "Add", "a": 0.0, "b": 0.0 }
+        }))
+        .unwrap();
+        let numbers = |numbers: &[f64]| {
+            numbers
+                .iter()
+                .copied()
+                .map(Value::Number)
+                .collect::<Vec<_>>()
+        };
+        let tree = |branches: &[(usize, &[f64])]| {
+            Value::Tree(DataTree::from_branches(
+                branches
+                    .iter()
+                    .map(|(index, items)| (Path::new([*index]), numbers(items))),
+            ))
+        };
+        let more_branches = tree(&[(0, &[1.0, 2.0]), (1, &[10.0])]);
+        // Smaller trees repeat their last branch
+        let longer_branch = tree(&[(0, &[100.0, 200.0, 300.0])]);
+        assert_eq!(
+            solve_matched(&node, vec![more_branches.clone(), longer_branch]).unwrap(),
+            vec![tree(&[
+                (0, &[101.0, 202.0, 302.0]),
+                (1, &[110.0, 210.0, 310.0])
+            ])]
+        );
+        // Lists are used as they are in every branch
+        let list = Value::List(numbers(&[1000.0, 2000.0]));
+        assert_eq!(
+            solve_matched(&node, vec![more_branches, list]).unwrap(),
+            vec![tree(&[(0, &[1001.0, 2002.0]), (1, &[1010.0, 2010.0])])]
+        );
+    }
+
+    #[test]
+    fn list_inputs_take_whole_lists_and_item_inputs_are_matched() {
+        let node = Nodes::Gradient(GradientNode::default());
+        let stops = Value::List(vec![
+            Value::Color(Color::rgb(0.0, 0.0, 0.0)),
+            Value::Color(Color::rgb(1.0, 1.0, 1.0)),
+        ]);
+        // A nested list of counts is matched recursively
+        let counts = Value::List(vec![
+            Value::Number(2.0),
+            Value::List(vec![Value::Number(3.0), Value::Number(1.0)]),
+        ]);
+        let outputs = solve_matched(&node, vec![stops, counts]).unwrap();
+        let lengths = |value: &Value| match value {
+            Value::List(items) => items.len(),
+            _ => panic!("Expected a list, got {value}"),
+        };
+        let [Value::List(gradients)] = &outputs[..] else {
+            panic!("Expected one list, got {outputs:?}");
+        };
+        assert_eq!(lengths(&gradients[0]), 2);
+        let Value::List(nested) = &gradients[1] else {
+            panic!("Expected a nested list, got {}", gradients[1]);
+        };
+        assert_eq!(nested.iter().map(lengths).collect::<Vec<_>>(), vec![3, 1]);
+    }
+}
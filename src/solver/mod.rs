@@ -0,0 +1,496 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet, VecDeque},
+    hash::{Hash, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use egui_snarl::{InPin, InPinId, NodeId, OutPinId, Snarl};
+
+use crate::{nodes::Nodes, value::Value};
+
+mod matching;
+
+pub use matching::{Access, ListMatching};
+
+/// Reasons why a node could not be evaluated
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    /// The node depends on its own output
+    Cycle,
+    /// An input received a value of a type the node can't work with
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// An input has neither a wire nor a value of its own,
+    /// or the node wired into it failed to evaluate
+    MissingInput { input: usize },
+    /// A number is outside of the range the node can work with
+    Domain(String),
+}
+
+impl std::fmt::Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cycle => write!(f, "Node is part of a cycle"),
+            Self::TypeMismatch { expected, found } => {
+                write!(f, "Expected a {expected}, but got a {found}")
+            }
+            Self::MissingInput { input } => write!(f, "Input {input} has no value"),
+            Self::Domain(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// The values of all output pins after solving a [`Snarl`],
+/// together with the errors of all nodes that failed to evaluate
+#[derive(Default, Debug)]
+pub struct Results {
+    values: HashMap<OutPinId, Value>,
+    inputs: HashMap<InPinId, Value>,
+    errors: HashMap<NodeId, EvalError>,
+    revisions: HashMap<NodeId, u64>,
+}
+
+impl Results {
+    pub fn get(&self, pin: OutPinId) -> Option<&Value> {
+        self.values.get(&pin)
+    }
+
+    /// The value arriving at an input pin over its wires,
+    /// merged as described in [`Value::merge`] for multiple wires
+    pub fn input(&self, pin: &InPin) -> Option<&Value> {
+        self.inputs.get(&pin.id)
+    }
+
+    pub fn error(&self, node: NodeId) -> Option<&EvalError> {
+        self.errors.get(&node)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = (NodeId, &EvalError)> + '_ {
+        self.errors.iter().map(|(node, error)| (*node, error))
+    }
+
+    /// Changes whenever the outputs or the error of a node change,
+    /// `None` for nodes that were never evaluated
+    pub fn revision(&self, node: NodeId) -> Option<u64> {
+        self.revisions.get(&node).copied()
+    }
+
+    /// All output values of the given node, in pin order
+    pub fn node_outputs(&self, node: NodeId, outputs: usize) -> Vec<Option<&Value>> {
+        (0..outputs)
+            .map(|output| self.get(OutPinId { node, output }))
+            .collect()
+    }
+
+    /// Only keeps the results of nodes matching the predicate
+    fn retain(&mut self, keep: impl Fn(NodeId) -> bool) {
+        self.values.retain(|pin, _| keep(pin.node));
+        self.inputs.retain(|pin, _| keep(pin.node));
+        self.errors.retain(|node, _| keep(*node));
+        self.revisions.retain(|node, _| keep(*node));
+    }
+}
+
+/// Solves the whole graph, without showing any ui.
+///
+/// Nodes are evaluated in topological order, so every node
+/// sees the final values of all nodes wired into it.
+/// A node failing to evaluate does not stop the solver, it is
+/// recorded in the [`Results`] and all nodes depending on it
+/// report a missing input.
+pub fn solve(snarl: &Snarl<Nodes>) -> Results {
+    let mut solver = Solver::default();
+    solver.solve(snarl);
+    solver.results
+}
+
+/// Source of [`Results::revision`], shared by all solvers so a
+/// revision never refers to the outputs of two different graphs
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+/// Solves a graph repeatedly, only evaluating the nodes whose
+/// values might have changed since the last time.
+///
+/// A node is dirty if its own state changed, or if the revision of
+/// any node wired into it changed. Clean nodes keep their results,
+/// dirty nodes are evaluated again and only get a new revision if their
+/// outputs actually differ, so the dirty flag travels downstream only
+/// as far as values change.
+///
+/// Changes to the state of a node already known to the solver have to
+/// be announced with [`Solver::edited`], wires are followed by the solver.
+#[derive(Default)]
+pub struct Solver {
+    /// Hash of the node state and the revisions of its inputs,
+    /// the last time the node was evaluated
+    keys: HashMap<NodeId, u64>,
+    /// Hash of the state of every node, so nodes don't have
+    /// to be serialized again on every solve
+    states: HashMap<NodeId, u64>,
+    /// Values replacing whatever arrives at an input pin
+    overrides: HashMap<InPinId, Value>,
+    results: Results,
+}
+
+impl Solver {
+    /// A solver which replaces the values of some input
+    /// pins, no matter if they are connected or not
+    pub fn with_overrides(overrides: HashMap<InPinId, Value>) -> Self {
+        Self {
+            overrides,
+            ..Default::default()
+        }
+    }
+
+    pub fn solve(&mut self, snarl: &Snarl<Nodes>) -> &Results {
+        let alive = snarl.node_ids().map(|(id, _)| id).collect::<HashSet<_>>();
+        self.keys.retain(|id, _| alive.contains(id));
+        self.states.retain(|id, _| alive.contains(id));
+        self.results.retain(|id| alive.contains(&id));
+
+        let (order, cycles) = topological_order(snarl);
+        for id in cycles {
+            self.keys.remove(&id);
+            self.store(snarl, id, Err(EvalError::Cycle));
+        }
+
+        for id in order {
+            let key = self.key(snarl, id);
+            if self.keys.get(&id) == Some(&key) {
+                continue;
+            }
+            self.keys.insert(id, key);
+            self.results.inputs.retain(|pin, _| pin.node != id);
+            let outcome = solve_node(snarl, &self.overrides, &mut self.results, id);
+            self.store(snarl, id, outcome);
+        }
+
+        &self.results
+    }
+
+    /// Replaces the results of a node, giving it a new
+    /// revision if they differ from the previous ones
+    fn store(&mut self, snarl: &Snarl<Nodes>, id: NodeId, outcome: Result<Vec<Value>, EvalError>) {
+        let outputs = snarl[id].outputs();
+        let previous = self.results.node_outputs(id, outputs);
+        let unchanged = match &outcome {
+            Ok(values) => {
+                self.results.error(id).is_none()
+                    && values.len() == outputs
+                    && values
+                        .iter()
+                        .zip(previous)
+                        .all(|(value, previous)| Some(value) == previous)
+            }
+            Err(error) => self.results.error(id) == Some(error),
+        };
+
+        self.results.values.retain(|pin, _| pin.node != id);
+        self.results.errors.remove(&id);
+        match outcome {
+            Ok(values) => {
+                for (output, value) in values.into_iter().enumerate() {
+                    self.results
+                        .values
+                        .insert(OutPinId { node: id, output }, value);
+                }
+            }
+            Err(error) => {
+                self.results.errors.insert(id, error);
+            }
+        }
+        if !unchanged || !self.results.revisions.contains_key(&id) {
+            let revision = NEXT_REVISION.fetch_add(1, Ordering::Relaxed);
+            self.results.revisions.insert(id, revision);
+        }
+    }
+
+    pub fn results(&self) -> &Results {
+        &self.results
+    }
+
+    /// Announces that the state of a node might have changed, so it is
+    /// evaluated again by the next solve if it did. Returns if it did
+    pub fn edited(&mut self, snarl: &Snarl<Nodes>, id: NodeId) -> bool {
+        let state = state(&snarl[id]);
+        self.states.insert(id, state) != Some(state)
+    }
+
+    /// Identifies everything a node's outputs depend on, which
+    /// is its own state and the revisions of the nodes wired into it
+    fn key(&mut self, snarl: &Snarl<Nodes>, id: NodeId) -> u64 {
+        let node = &snarl[id];
+        let mut hasher = DefaultHasher::new();
+        self.states
+            .entry(id)
+            .or_insert_with(|| state(node))
+            .hash(&mut hasher);
+        for input in 0..node.inputs() {
+            let mut remotes = snarl.in_pin(InPinId { node: id, input }).remotes;
+            remotes.sort();
+            input.hash(&mut hasher);
+            for remote in remotes {
+                (remote.node.0, remote.output).hash(&mut hasher);
+                self.results.revision(remote.node).hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+/// Hash of everything stored in a node
+fn state(node: &Nodes) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(node)
+        .expect("Nodes can always be serialized")
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+fn solve_node(
+    snarl: &Snarl<Nodes>,
+    overrides: &HashMap<InPinId, Value>,
+    results: &mut Results,
+    id: NodeId,
+) -> Result<Vec<Value>, EvalError> {
+    let node = &snarl[id];
+    let inputs = (0..node.inputs())
+        .map(|input| {
+            input_value(snarl, overrides, results, InPinId { node: id, input })
+                .ok_or(EvalError::MissingInput { input })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    matching::solve_matched(node, inputs)
+}
+
+/// The value on an input pin, which is either the value of the
+/// outputs wired into it, or the nodes own value for that input.
+/// Overrides replace both of these.
+///
+/// Values of multiple wires are merged in the order of the nodes they
+/// come from, so the oldest node is drawn first and the order stays
+/// the same after saving and loading a graph
+fn input_value(
+    snarl: &Snarl<Nodes>,
+    overrides: &HashMap<InPinId, Value>,
+    results: &mut Results,
+    pin: InPinId,
+) -> Option<Value> {
+    let mut remotes = snarl.in_pin(pin).remotes;
+    remotes.sort();
+    let value = match remotes[..] {
+        _ if overrides.contains_key(&pin) => overrides[&pin].clone(),
+        [] => return snarl[pin.node].default_input(pin.input),
+        [remote] => results.get(remote)?.clone(),
+        _ => Value::merge(
+            remotes
+                .iter()
+                .map(|remote| results.get(*remote).cloned())
+                .collect::<Option<Vec<_>>>()?,
+        ),
+    };
+    results.inputs.insert(pin, value.clone());
+    Some(value)
+}
+
+/// Orders the nodes so every node comes after all nodes wired into it.
+///
+/// Nodes that are part of a cycle can never be ordered, they are
+/// returned separately. Nodes that only depend on a cycle are
+/// appended at the end of the order, as they can't be solved anyways.
+fn topological_order(snarl: &Snarl<Nodes>) -> (Vec<NodeId>, HashSet<NodeId>) {
+    let mut in_degree: HashMap<NodeId, usize> = HashMap::new();
+    let mut downstream: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+    for (id, node) in snarl.node_ids() {
+        in_degree.entry(id).or_default();
+        for input in 0..node.inputs() {
+            for remote in snarl.in_pin(InPinId { node: id, input }).remotes {
+                *in_degree.entry(id).or_default() += 1;
+                downstream.entry(remote.node).or_default().push(id);
+            }
+        }
+    }
+
+    let mut ready = in_degree
+        .iter()
+        .filter(|(_, degree)| **degree == 0)
+        .map(|(id, _)| *id)
+        .collect::<Vec<_>>();
+    ready.sort();
+    let mut ready = VecDeque::from(ready);
+
+    let mut order = Vec::with_capacity(in_degree.len());
+    while let Some(id) = ready.pop_front() {
+        order.push(id);
+        for next in downstream.get(&id).into_iter().flatten() {
+            let degree = in_degree.get_mut(next).expect("Every node has a degree");
+            *degree -= 1;
+            if *degree == 0 {
+                ready.push_back(*next);
+            }
+        }
+    }
+
+    let mut unordered = in_degree
+        .into_iter()
+        .filter(|(_, degree)| *degree > 0)
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    unordered.sort();
+    let cycles = unordered
+        .iter()
+        .copied()
+        .filter(|id| reaches(&downstream, *id, *id))
+        .collect::<HashSet<_>>();
+    order.extend(unordered.into_iter().filter(|id| !cycles.contains(id)));
+
+    (order, cycles)
+}
+
+/// Checks if there is a path of wires leading from one node to another
+fn reaches(downstream: &HashMap<NodeId, Vec<NodeId>>, from: NodeId, to: NodeId) -> bool {
+    let mut visited = HashSet::new();
+    let mut stack = downstream.get(&from).cloned().unwrap_or_default();
+    while let Some(id) = stack.pop() {
+        if id == to {
+            return true;
+        }
+        if visited.insert(id) {
+            stack.extend(downstream.get(&id).into_iter().flatten());
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use egui_snarl::{InPinId, NodeId, OutPinId, Snarl};
+
+    use super::*;
+    use crate::nodes::math::Operation;
+
+    fn math(operation: &str, a: f64, b: f64) -> Nodes {
+        serde_json::from_value(serde_json::json!({
+            "Math": { "operation": operation, "a": a, "b": b }
+        }))
+        .unwrap()
+    }
+
+    fn wire(snarl: &mut Snarl<Nodes>, from: NodeId, to: NodeId, input: usize) {
+        snarl.connect(
+            OutPinId {
+                node: from,
+                output: 0,
+            },
+            InPinId { node: to, input },
+        );
+    }
+
+    fn output(results: &Results, node: NodeId) -> Option<&Value> {
+        results.get(OutPinId { node, output: 0 })
+    }
+
+    #[test]
+    fn nodes_are_solved_after_their_inputs() {
+        let mut snarl = Snarl::new();
+        // Inserted downstream first, so the ids are not in solving order
+        let sum = snarl.insert_node(egui::Pos2::ZERO, math("Add", 0.0, 10.0));
+        let product = snarl.insert_node(egui::Pos2::ZERO, math("Multiply", 0.0, 3.0));
+        let first = snarl.insert_node(egui::Pos2::ZERO, math("Add", 1.0, 1.0));
+        wire(&mut snarl, first, product, 0);
+        wire(&mut snarl, product, sum, 0);
+
+        let results = solve(&snarl);
+        assert_eq!(output(&results, first), Some(&Value::Number(2.0)));
+        assert_eq!(output(&results, product), Some(&Value::Number(6.0)));
+        assert_eq!(output(&results, sum), Some(&Value::Number(16.0)));
+        assert_eq!(results.errors().count(), 0);
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        let mut snarl = Snarl::new();
+        let a = snarl.insert_node(egui::Pos2::ZERO, math("Add", 1.0, 1.0));
+        let b = snarl.insert_node(egui::Pos2::ZERO, math("Add", 1.0, 1.0));
+        let after = snarl.insert_node(egui::Pos2::ZERO, math("Add", 1.0, 1.0));
+        let unrelated = snarl.insert_node(egui::Pos2::ZERO, math("Add", 1.0, 1.0));
+        wire(&mut snarl, a, b, 0);
+        wire(&mut snarl, b, a, 0);
+        wire(&mut snarl, b, after, 1);
+
+        let results = solve(&snarl);
+        assert_eq!(results.error(a), Some(&EvalError::Cycle));
+        assert_eq!(results.error(b), Some(&EvalError::Cycle));
+        // Only depending on a cycle is not being part of it
+        assert_eq!(
+            results.error(after),
+            Some(&EvalError::MissingInput { input: 1 })
+        );
+        assert_eq!(output(&results, unrelated), Some(&Value::Number(2.0)));
+    }
+
+    #[test]
+    fn unchanged_nodes_keep_their_revision() {
+        let mut snarl = Snarl::new();
+        let first = snarl.insert_node(egui::Pos2::ZERO, math("Add", 2.0, 2.0));
+        let second = snarl.insert_node(egui::Pos2::ZERO, math("Multiply", 0.0, 3.0));
+        let other = snarl.insert_node(egui::Pos2::ZERO, math("Add", 5.0, 5.0));
+        wire(&mut snarl, first, second, 0);
+
+        let mut solver = Solver::default();
+        let results = solver.solve(&snarl);
+        let revisions = [first, second, other].map(|node| results.revision(node));
+        assert!(revisions.iter().all(Option::is_some));
+        let results = solver.solve(&snarl);
+        assert_eq!(
+            [first, second, other].map(|node| results.revision(node)),
+            revisions
+        );
+
+        // 2 * 2 is still 4, so nothing downstream changes
+        if let Some(Nodes::Math(node)) = snarl.get_node_mut(first) {
+            node.operation = Operation::Multiply;
+        }
+        assert!(solver.edited(&snarl, first));
+        let results = solver.solve(&snarl);
+        assert_eq!(output(results, second), Some(&Value::Number(12.0)));
+        assert_eq!(
+            [first, second, other].map(|node| results.revision(node)),
+            revisions
+        );
+
+        // 2 - 2 is not, so the node and everything downstream changes
+        if let Some(Nodes::Math(node)) = snarl.get_node_mut(first) {
+            node.operation = Operation::Subtract;
+        }
+        assert!(solver.edited(&snarl, first));
+        // Announcing a node which didn't change is harmless
+        assert!(!solver.edited(&snarl, other));
+        let results = solver.solve(&snarl);
+        assert_eq!(output(results, second), Some(&Value::Number(0.0)));
+        assert_ne!(results.revision(first), revisions[0]);
+        assert_ne!(results.revision(second), revisions[1]);
+        assert_eq!(results.revision(other), revisions[2]);
+    }
+
+    #[test]
+    fn overrides_replace_inputs() {
+        let mut snarl = Snarl::new();
+        let first = snarl.insert_node(egui::Pos2::ZERO, math("Add", 1.0, 1.0));
+        let second = snarl.insert_node(egui::Pos2::ZERO, math("Add", 0.0, 1.0));
+        wire(&mut snarl, first, second, 0);
+        let pin = InPinId {
+            node: second,
+            input: 0,
+        };
+        let mut solver = Solver::with_overrides(HashMap::from([(pin, Value::Number(7.0))]));
+        assert_eq!(
+            output(solver.solve(&snarl), second),
+            Some(&Value::Number(8.0))
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use crate::color::Color;
+
+/// How the ends of open lines and dashes are drawn
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+impl LineCap {
+    pub const ALL: [Self; 3] = [Self::Butt, Self::Round, Self::Square];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Butt => "Butt",
+            Self::Round => "Round",
+            Self::Square => "Square",
+        }
+    }
+
+    pub fn to_piet(self) -> piet::LineCap {
+        match self {
+            Self::Butt => piet::LineCap::Butt,
+            Self::Round => piet::LineCap::Round,
+            Self::Square => piet::LineCap::Square,
+        }
+    }
+}
+
+/// How the corners between two segments of a line are drawn
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+impl LineJoin {
+    pub const ALL: [Self; 3] = [Self::Miter, Self::Round, Self::Bevel];
+    /// Miters longer than this many line widths are beveled instead,
+    /// which is the default of svg, pdf and tiny-skia alike
+    pub const MITER_LIMIT: f64 = 4.0;
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Miter => "Miter",
+            Self::Round => "Round",
+            Self::Bevel => "Bevel",
+        }
+    }
+
+    pub fn to_piet(self) -> piet::LineJoin {
+        match self {
+            Self::Miter => piet::LineJoin::Miter {
+                limit: Self::MITER_LIMIT,
+            },
+            Self::Round => piet::LineJoin::Round,
+            Self::Bevel => piet::LineJoin::Bevel,
+        }
+    }
+}
+
+/// How a shape is painted. The default style strokes
+/// the outline black and one unit wide, without a fill
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Style {
+    /// Paints the inside of the shape, `None` leaves it empty
+    pub fill: Option<Color>,
+    /// Paints the outline of the shape, `None` leaves it out
+    pub stroke: Option<Color>,
+    /// Width of the outline, in units of the canvas
+    pub width: f64,
+    /// Alternating lengths of dashes and gaps, a solid line if empty
+    pub dash: Vec<f64>,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// Multiplies the alpha of both the fill and the stroke
+    pub opacity: f64,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl Style {
+    pub const DEFAULT: Self = Self {
+        fill: None,
+        stroke: Some(Color::BLACK),
+        width: 1.0,
+        dash: Vec::new(),
+        cap: LineCap::Butt,
+        join: LineJoin::Miter,
+        opacity: 1.0,
+    };
+
+    /// The fill color with the opacity applied, `None` if nothing is filled
+    pub fn fill_color(&self) -> Option<Color> {
+        self.fill
+            .map(|color| self.apply_opacity(color))
+            .filter(|color| color.a > 0.0)
+    }
+
+    /// The stroke color with the opacity applied,
+    /// `None` if no outline is drawn
+    pub fn stroke_color(&self) -> Option<Color> {
+        self.stroke
+            .filter(|_| self.width > 0.0)
+            .map(|color| self.apply_opacity(color))
+            .filter(|color| color.a > 0.0)
+    }
+
+    /// The dash pattern with an even number of lengths, like svg repeats
+    /// odd patterns twice. `None` for solid lines and unusable patterns
+    pub fn dashes(&self) -> Option<Vec<f64>> {
+        let usable = self
+            .dash
+            .iter()
+            .all(|length| length.is_finite() && *length >= 0.0)
+            && self.dash.iter().sum::<f64>() > 0.0;
+        if !usable {
+            return None;
+        }
+        let mut dashes = self.dash.clone();
+        if dashes.len() % 2 == 1 {
+            dashes.extend_from_within(..);
+        }
+        Some(dashes)
+    }
+
+    pub fn to_piet_stroke(&self) -> piet::StrokeStyle {
+        let mut stroke = piet::StrokeStyle::new();
+        stroke.set_line_cap(self.cap.to_piet());
+        stroke.set_line_join(self.join.to_piet());
+        if let Some(dashes) = self.dashes() {
+            stroke.set_dash_pattern(dashes);
+        }
+        stroke
+    }
+
+    fn apply_opacity(&self, color: Color) -> Color {
+        Color {
+            a: color.a * self.opacity.clamp(0.0, 1.0),
+            ..color
+        }
+    }
+}
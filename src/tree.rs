@@ -0,0 +1,391 @@
+use std::collections::BTreeMap;
+
+use crate::{solver::EvalError, value::Value};
+
+/// Address of a branch in a [`DataTree`], written as `{0;2;1}`
+#[derive(
+    serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub struct Path(pub Vec<usize>);
+
+impl Path {
+    pub fn new(indices: impl Into<Vec<usize>>) -> Self {
+        Self(indices.into())
+    }
+
+    /// A new path with one more index appended
+    pub fn push(&self, index: usize) -> Self {
+        let mut indices = self.0.clone();
+        indices.push(index);
+        Self(indices)
+    }
+}
+
+impl std::fmt::Display for Path {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indices = self
+            .0
+            .iter()
+            .map(|index| index.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{{{}}}", indices.join(";"))
+    }
+}
+
+impl std::str::FromStr for Path {
+    type Err = EvalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || EvalError::Domain(format!("'{s}' is not a valid path"));
+        let inner = s
+            .trim()
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .ok_or_else(invalid)?;
+        inner
+            .split(';')
+            .map(|index| index.trim().parse().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self)
+    }
+}
+
+/// A single branch of a [`DataTree`]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct Branch {
+    pub path: Path,
+    pub items: Vec<Value>,
+}
+
+/// Nested data, stored as a flat list of branches which
+/// are each addressed by a [`Path`] and sorted by it
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct DataTree {
+    branches: Vec<Branch>,
+}
+
+impl DataTree {
+    pub fn from_branches(branches: impl IntoIterator<Item = (Path, Vec<Value>)>) -> Self {
+        let mut merged: BTreeMap<Path, Vec<Value>> = BTreeMap::new();
+        for (path, items) in branches {
+            merged.entry(path).or_default().extend(items);
+        }
+        Self {
+            branches: merged
+                .into_iter()
+                .map(|(path, items)| Branch { path, items })
+                .collect(),
+        }
+    }
+
+    /// Turns any value into a tree. Lists of lists become one branch
+    /// per inner list, all other values end up in the single branch `{0}`
+    pub fn from_value(value: Value) -> Self {
+        match value {
+            Value::Tree(tree) => tree,
+            Value::List(items) if items.iter().all(|item| matches!(item, Value::List(_))) => {
+                Self::from_branches(items.into_iter().enumerate().map(|(index, item)| {
+                    let Value::List(items) = item else {
+                        unreachable!()
+                    };
+                    (Path::new([index]), items)
+                }))
+            }
+            Value::List(items) => Self::from_branches([(Path::new([0]), items)]),
+            item => Self::from_branches([(Path::new([0]), vec![item])]),
+        }
+    }
+
+    pub fn branches(&self) -> &[Branch] {
+        &self.branches
+    }
+
+    pub fn branch(&self, path: &Path) -> Option<&Branch> {
+        self.branches.iter().find(|branch| &branch.path == path)
+    }
+
+    /// All items of all branches, in branch order
+    pub fn items(&self) -> impl Iterator<Item = &Value> + '_ {
+        self.branches.iter().flat_map(|branch| branch.items.iter())
+    }
+
+    /// Moves every item into a branch of its own
+    pub fn graft(&self) -> Self {
+        Self::from_branches(self.branches.iter().flat_map(|branch| {
+            branch
+                .items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| (branch.path.push(index), vec![item.clone()]))
+        }))
+    }
+
+    /// Collects all items into the single branch `{0}`
+    pub fn flatten(&self) -> Self {
+        Self::from_branches([(Path::new([0]), self.items().cloned().collect())])
+    }
+
+    /// Removes the indices all paths start with
+    pub fn simplify(&self) -> Self {
+        let Some(shortest) = self.branches.iter().map(|branch| branch.path.0.len()).min() else {
+            return self.clone();
+        };
+        let first = &self.branches[0].path.0;
+        let mut shared = (0..shortest)
+            .take_while(|&i| {
+                self.branches
+                    .iter()
+                    .all(|branch| branch.path.0[i] == first[i])
+            })
+            .count();
+        if self.branches.len() > 1 {
+            shared = shared.min(shortest - 1);
+        }
+        Self::from_branches(self.branches.iter().map(|branch| {
+            let mut path = branch.path.0[shared..].to_vec();
+            if path.is_empty() {
+                path.push(0);
+            }
+            (Path(path), branch.items.clone())
+        }))
+    }
+
+    /// Moves items into new branches, as described by a [`PathMapping`]
+    pub fn map_paths(&self, mapping: &PathMapping) -> Result<Self, EvalError> {
+        let mut branches = Vec::new();
+        for branch in &self.branches {
+            for (index, item) in branch.items.iter().enumerate() {
+                let path = mapping.apply(&branch.path, index)?;
+                branches.push((path, vec![item.clone()]));
+            }
+        }
+        Ok(Self::from_branches(branches))
+    }
+}
+
+/// A single index in a [`PathMapping`]
+#[derive(Debug, Clone, PartialEq)]
+enum PathIndex {
+    /// Binds to the path index at the same position in the source
+    Variable(char),
+    /// The index of the item inside of its branch
+    Item,
+    Fixed(usize),
+}
+
+/// Maps the paths of a tree to new paths, written as `{A;B} -> {B;A;i}`.
+///
+/// Upper case letters in the source bind the path indices, which
+/// can then be reordered or dropped in the target. The target may also
+/// hold fixed numbers and `i`, which is the index of the item in its branch
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathMapping {
+    source: Vec<char>,
+    target: Vec<PathIndex>,
+}
+
+impl std::str::FromStr for PathMapping {
+    type Err = EvalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || EvalError::Domain(format!("'{s}' is not a valid path mapping"));
+        let (source, target) = s.split_once("->").ok_or_else(invalid)?;
+        let indices = |part: &str| {
+            part.trim()
+                .strip_prefix('{')
+                .and_then(|s| s.strip_suffix('}'))
+                .map(|inner| {
+                    inner
+                        .split(';')
+                        .map(|index| index.trim().to_string())
+                        .collect::<Vec<_>>()
+                })
+                .ok_or_else(invalid)
+        };
+
+        let source = indices(source)?
+            .into_iter()
+            .map(|index| match index.chars().collect::<Vec<_>>()[..] {
+                [variable] if variable.is_ascii_uppercase() => Ok(variable),
+                _ => Err(invalid()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // A variable bound twice would leave it open which index it takes
+        if (1..source.len()).any(|i| source[..i].contains(&source[i])) {
+            return Err(invalid());
+        }
+        let target = indices(target)?
+            .into_iter()
+            .map(|index| match index.chars().collect::<Vec<_>>()[..] {
+                ['i'] => Ok(PathIndex::Item),
+                [variable] if source.contains(&variable) => Ok(PathIndex::Variable(variable)),
+                _ => index.parse().map(PathIndex::Fixed).map_err(|_| invalid()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { source, target })
+    }
+}
+
+impl PathMapping {
+    fn apply(&self, path: &Path, item: usize) -> Result<Path, EvalError> {
+        if path.0.len() != self.source.len() {
+            return Err(EvalError::Domain(format!(
+                "Path {path} does not match a mapping with {} indices",
+                self.source.len()
+            )));
+        }
+        let indices = self
+            .target
+            .iter()
+            .map(|index| match index {
+                PathIndex::Variable(variable) => {
+                    let position = self
+                        .source
+                        .iter()
+                        .position(|source| source == variable)
+                        .expect("Target variables are bound in the source");
+                    path.0[position]
+                }
+                PathIndex::Item => item,
+                PathIndex::Fixed(index) => *index,
+            })
+            .collect();
+        Ok(Path(indices))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(numbers: &[f64]) -> Vec<Value> {
+        numbers.iter().copied().map(Value::Number).collect()
+    }
+
+    fn tree(branches: &[(&[usize], &[f64])]) -> DataTree {
+        DataTree::from_branches(
+            branches
+                .iter()
+                .map(|(path, items)| (Path::new(*path), numbers(items))),
+        )
+    }
+
+    #[test]
+    fn paths_are_parsed_and_written() {
+        let path = " { 0; 2 ;1 } ".parse::<Path>().unwrap();
+        assert_eq!(path, Path::new([0, 2, 1]));
+        assert_eq!(path.to_string(), "{0;2;1}");
+        for invalid in ["", "{}", "0;1", "{0;1", "{0;;1}", "{a}", "{-1}"] {
+            assert_eq!(
+                invalid.parse::<Path>(),
+                Err(EvalError::Domain(format!(
+                    "'{invalid}' is not a valid path"
+                ))),
+            );
+        }
+    }
+
+    #[test]
+    fn path_mappings_are_parsed() {
+        let mapping = "{A;B}->{B;A;i}".parse::<PathMapping>().unwrap();
+        assert_eq!(mapping.source, vec!['A', 'B']);
+        assert_eq!(
+            mapping.target,
+            vec![
+                PathIndex::Variable('B'),
+                PathIndex::Variable('A'),
+                PathIndex::Item
+            ]
+        );
+        let mapping = " {A} -> { 3 ; A } ".parse::<PathMapping>().unwrap();
+        assert_eq!(
+            mapping.target,
+            vec![PathIndex::Fixed(3), PathIndex::Variable('A')]
+        );
+        for invalid in [
+            "{A;B}",
+            "{A;B}->",
+            "{A;B}->{C}",
+            "{a}->{a}",
+            "{AB}->{A}",
+            "{0}->{0}",
+            "{A;A}->{A}",
+            "A->{A}",
+            "{A}->{x}",
+        ] {
+            assert!(
+                invalid.parse::<PathMapping>().is_err(),
+                "'{invalid}' should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn path_mappings_move_items() {
+        let source = tree(&[(&[0, 0], &[1.0, 2.0]), (&[0, 1], &[3.0])]);
+        let mapping = "{A;B}->{B;A;i}".parse::<PathMapping>().unwrap();
+        assert_eq!(
+            source.map_paths(&mapping).unwrap(),
+            tree(&[
+                (&[0, 0, 0], &[1.0]),
+                (&[0, 0, 1], &[2.0]),
+                (&[1, 0, 0], &[3.0]),
+            ])
+        );
+        // Dropping an index merges the branches
+        let mapping = "{A;B}->{A}".parse::<PathMapping>().unwrap();
+        assert_eq!(
+            source.map_paths(&mapping).unwrap(),
+            tree(&[(&[0], &[1.0, 2.0, 3.0])])
+        );
+        let mapping = "{A}->{A}".parse::<PathMapping>().unwrap();
+        assert!(matches!(
+            source.map_paths(&mapping),
+            Err(EvalError::Domain(_))
+        ));
+    }
+
+    #[test]
+    fn graft_moves_every_item_into_its_own_branch() {
+        let source = tree(&[(&[0], &[1.0, 2.0]), (&[1], &[3.0])]);
+        assert_eq!(
+            source.graft(),
+            tree(&[(&[0, 0], &[1.0]), (&[0, 1], &[2.0]), (&[1, 0], &[3.0]),])
+        );
+    }
+
+    #[test]
+    fn flatten_collects_all_items_in_order() {
+        let source = tree(&[(&[1, 0], &[3.0]), (&[0, 2], &[1.0, 2.0])]);
+        assert_eq!(source.flatten(), tree(&[(&[0], &[1.0, 2.0, 3.0])]));
+    }
+
+    #[test]
+    fn simplify_removes_shared_indices() {
+        let source = tree(&[(&[0, 4, 0], &[1.0]), (&[0, 4, 1], &[2.0])]);
+        assert_eq!(source.simplify(), tree(&[(&[0], &[1.0]), (&[1], &[2.0])]));
+        // Branches stay apart, even if one path is the start of another
+        let source = tree(&[(&[2], &[1.0]), (&[2, 5], &[2.0])]);
+        assert_eq!(source.simplify(), source);
+        // A single branch keeps one index
+        let source = tree(&[(&[3, 1], &[1.0])]);
+        assert_eq!(source.simplify(), tree(&[(&[0], &[1.0])]));
+    }
+
+    #[test]
+    fn values_become_trees() {
+        let nested = Value::List(vec![
+            Value::List(numbers(&[1.0])),
+            Value::List(numbers(&[2.0, 3.0])),
+        ]);
+        assert_eq!(
+            DataTree::from_value(nested),
+            tree(&[(&[0], &[1.0]), (&[1], &[2.0, 3.0])])
+        );
+        assert_eq!(
+            DataTree::from_value(Value::Number(1.0)),
+            tree(&[(&[0], &[1.0])])
+        );
+    }
+}
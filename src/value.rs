@@ -0,0 +1,470 @@
+use crate::{
+    color::Color,
+    nodes::{format_float, format_point},
+    nurbs::Nurbs,
+    render::Drawing,
+    shapes::Shapes,
+    solver::EvalError,
+    tree::DataTree,
+};
+
+/// A value flowing along the wires of the node graph.
+/// Every output pin of a node produces exactly one of these.
+///
+/// Nodes read their inputs through the `as_*` accessors, which
+/// apply these implicit conversions if the types don't match:
+///
+/// | From     | To     | Conversion                         |
+/// |----------|--------|------------------------------------|
+/// | `Bool`   | Number | `1` for `true`, `0` for `false`    |
+/// | `Text`   | Number | Parsed, if it is a valid number    |
+/// | `Number` | Bool   | `true` for everything except `0`   |
+/// | `Number` | Point  | Broadcast to both coordinates      |
+/// | `Shape`  | Point  | See [`Shapes::center`]             |
+/// | `Curve`  | Shape  | See [`Nurbs::to_bezier`]           |
+/// | `Number` | Color  | Gray with the number as brightness |
+/// | any      | Text   | The formatted value                |
+/// | any      | Tree   | See [`DataTree::from_value`]       |
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Bool(bool),
+    Text(String),
+    Point(piet::kurbo::Point),
+    Color(Color),
+    Shape(Shapes),
+    Curve(Nurbs),
+    Transform(piet::kurbo::Affine),
+    Drawing(Drawing),
+    List(Vec<Value>),
+    Tree(DataTree),
+}
+
+impl Value {
+    /// Human readable name of the values type
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Number(_) => "Number",
+            Self::Bool(_) => "Bool",
+            Self::Text(_) => "Text",
+            Self::Point(_) => "Point",
+            Self::Color(_) => "Color",
+            Self::Shape(_) => "Shape",
+            Self::Curve(_) => "Curve",
+            Self::Transform(_) => "Transform",
+            Self::Drawing(_) => "Drawing",
+            Self::List(_) => "List",
+            Self::Tree(_) => "Tree",
+        }
+    }
+
+    fn mismatch(&self, expected: &'static str) -> EvalError {
+        EvalError::TypeMismatch {
+            expected,
+            found: self.type_name(),
+        }
+    }
+
+    pub fn as_number(&self) -> Result<f64, EvalError> {
+        match self {
+            Self::Number(value) => Ok(*value),
+            Self::Bool(value) => Ok(if *value { 1.0 } else { 0.0 }),
+            Self::Text(value) => {
+                let number: f64 = value.trim().parse().map_err(|_| self.mismatch("Number"))?;
+                // Parsing accepts "NaN", "inf" and overflowing exponents,
+                // which would reach every node through text parameters
+                if number.is_finite() {
+                    Ok(number)
+                } else {
+                    Err(EvalError::Domain(format!(
+                        "Text has to be a finite number, but is \"{value}\""
+                    )))
+                }
+            }
+            _ => Err(self.mismatch("Number")),
+        }
+    }
+
+    /// Largest count of items a single node may make, so a huge number
+    /// cannot exhaust the memory of the editor or the server
+    pub const MAX_COUNT: usize = 100_000;
+
+    /// A number used to count items, which has to be finite, positive
+    /// and at most [`Value::MAX_COUNT`]
+    pub fn as_count(&self) -> Result<usize, EvalError> {
+        let value = self.as_number()?;
+        if !(value.is_finite() && value >= 0.0) {
+            Err(EvalError::Domain(format!(
+                "Count has to be a positive number, but is {value}"
+            )))
+        } else if value > Self::MAX_COUNT as f64 {
+            Err(EvalError::Domain(format!(
+                "Count can be at most {}, but is {value}",
+                Self::MAX_COUNT
+            )))
+        } else {
+            Ok(value as usize)
+        }
+    }
+
+    pub fn as_bool(&self) -> Result<bool, EvalError> {
+        match self {
+            Self::Bool(value) => Ok(*value),
+            Self::Number(value) => Ok(*value != 0.0),
+            _ => Err(self.mismatch("Bool")),
+        }
+    }
+
+    pub fn as_text(&self) -> Result<String, EvalError> {
+        match self {
+            Self::Text(value) => Ok(value.clone()),
+            Self::List(_) | Self::Tree(_) => Err(self.mismatch("Text")),
+            _ => Ok(self.to_string()),
+        }
+    }
+
+    pub fn as_point(&self) -> Result<piet::kurbo::Point, EvalError> {
+        match self {
+            Self::Point(value) => Ok(*value),
+            Self::Number(value) => Ok(piet::kurbo::Point::new(*value, *value)),
+            Self::Shape(shape) => Ok(shape.center()),
+            _ => Err(self.mismatch("Point")),
+        }
+    }
+
+    pub fn as_color(&self) -> Result<Color, EvalError> {
+        match self {
+            Self::Color(value) => Ok(*value),
+            Self::Number(value) => Ok(Color::rgb(*value, *value, *value)),
+            Self::Text(value) => Color::from_hex(value).ok_or_else(|| self.mismatch("Color")),
+            _ => Err(self.mismatch("Color")),
+        }
+    }
+
+    pub fn as_shape(&self) -> Result<Shapes, EvalError> {
+        match self {
+            Self::Shape(value) => Ok(value.clone()),
+            Self::Curve(curve) => Ok(Shapes::Path(curve.to_bezier(Nurbs::TOLERANCE))),
+            _ => Err(self.mismatch("Shape")),
+        }
+    }
+
+    pub fn as_curve(&self) -> Result<&Nurbs, EvalError> {
+        match self {
+            Self::Curve(value) => Ok(value),
+            _ => Err(self.mismatch("Curve")),
+        }
+    }
+
+    pub fn as_transform(&self) -> Result<piet::kurbo::Affine, EvalError> {
+        match self {
+            Self::Transform(value) => Ok(*value),
+            _ => Err(self.mismatch("Transform")),
+        }
+    }
+
+    /// Applies a transform to points, shapes and curves,
+    /// and to every item of lists and trees of them
+    pub fn transform(&self, affine: piet::kurbo::Affine) -> Result<Value, EvalError> {
+        match self {
+            Self::Point(point) => Ok(Self::Point(affine * *point)),
+            Self::Shape(shape) => Ok(Self::Shape(shape.transform(affine))),
+            Self::Curve(curve) => Ok(Self::Curve(curve.transform(affine))),
+            Self::List(values) => values
+                .iter()
+                .map(|value| value.transform(affine))
+                .collect::<Result<_, _>>()
+                .map(Self::List),
+            Self::Tree(tree) => tree
+                .branches()
+                .iter()
+                .map(|branch| {
+                    let items = branch
+                        .items
+                        .iter()
+                        .map(|value| value.transform(affine))
+                        .collect::<Result<_, _>>()?;
+                    Ok((branch.path.clone(), items))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(|branches| Self::Tree(DataTree::from_branches(branches))),
+            _ => Err(self.mismatch("Geometry")),
+        }
+    }
+
+    pub fn as_drawing(&self) -> Result<&Drawing, EvalError> {
+        match self {
+            Self::Drawing(value) => Ok(value),
+            _ => Err(self.mismatch("Drawing")),
+        }
+    }
+
+    /// Reads a value from plain json, as it is passed in from outside of
+    /// the editor. Arrays become lists and objects with only `x` and `y`
+    /// become points, all other objects have to be serialized values
+    pub fn from_json(json: &serde_json::Value) -> Result<Self, serde_json::Error> {
+        match json {
+            serde_json::Value::Number(number) => {
+                Ok(Self::Number(number.as_f64().unwrap_or_default()))
+            }
+            serde_json::Value::Bool(value) => Ok(Self::Bool(*value)),
+            serde_json::Value::String(value) => Ok(Self::Text(value.clone())),
+            serde_json::Value::Array(values) => values
+                .iter()
+                .map(Self::from_json)
+                .collect::<Result<_, _>>()
+                .map(Self::List),
+            serde_json::Value::Object(object)
+                if object.len() == 2 && object.contains_key("x") && object.contains_key("y") =>
+            {
+                serde_json::from_value(json.clone()).map(Self::Point)
+            }
+            _ => serde_json::from_value(json.clone()),
+        }
+    }
+
+    /// The value as plain json, the opposite of [`Value::from_json`]
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::Number(number) => serde_json::json!(number),
+            Self::Bool(value) => serde_json::json!(value),
+            Self::Text(value) => serde_json::json!(value),
+            Self::Point(point) => serde_json::json!({ "x": point.x, "y": point.y }),
+            Self::Color(color) => serde_json::json!(color.to_hex()),
+            Self::List(values) => values.iter().map(Self::to_json).collect(),
+            _ => serde_json::to_value(self).expect("Values can always be serialized"),
+        }
+    }
+
+    /// Merges the values of multiple wires into a single value.
+    ///
+    /// Trees are merged branch by branch, with all other values
+    /// wrapped into a tree first if any of the values is a tree.
+    /// Otherwise the result is a list holding all items, lists
+    /// are unpacked so their items end up in the same list
+    pub fn merge(values: Vec<Value>) -> Value {
+        if values.iter().any(|value| matches!(value, Self::Tree(_))) {
+            return Self::Tree(DataTree::from_branches(values.into_iter().flat_map(
+                |value| {
+                    DataTree::from_value(value)
+                        .branches()
+                        .iter()
+                        .map(|branch| (branch.path.clone(), branch.items.clone()))
+                        .collect::<Vec<_>>()
+                },
+            )));
+        }
+        Self::List(
+            values
+                .into_iter()
+                .flat_map(|value| match value {
+                    Self::List(items) => items,
+                    value => vec![value],
+                })
+                .collect(),
+        )
+    }
+
+    /// Converts the value into the given type, as allowed by [`ValueType::converts_to`]
+    pub fn convert(&self, to: ValueType) -> Result<Value, EvalError> {
+        match to {
+            ValueType::Any => Ok(self.clone()),
+            ValueType::Number => self.as_number().map(Self::Number),
+            ValueType::Bool => self.as_bool().map(Self::Bool),
+            ValueType::Text => self.as_text().map(Self::Text),
+            ValueType::Point => self.as_point().map(Self::Point),
+            ValueType::Color => self.as_color().map(Self::Color),
+            ValueType::Shape => self.as_shape().map(Self::Shape),
+            ValueType::Curve => self.as_curve().cloned().map(Self::Curve),
+            ValueType::Transform => self.as_transform().map(Self::Transform),
+            ValueType::Drawing => self.as_drawing().cloned().map(Self::Drawing),
+        }
+    }
+
+    /// The value as a tree, wrapping it into a tree if it is none
+    pub fn to_tree(&self) -> DataTree {
+        DataTree::from_value(self.clone())
+    }
+
+    /// Collects all shapes of a single shape, a list of shapes
+    /// or a tree of shapes, flattening nested lists
+    pub fn to_shapes(&self) -> Result<Vec<Shapes>, EvalError> {
+        match self {
+            Self::Shape(_) | Self::Curve(_) => Ok(vec![self.as_shape()?]),
+            Self::List(values) => values.iter().try_fold(Vec::new(), |mut shapes, value| {
+                shapes.extend(value.to_shapes()?);
+                Ok(shapes)
+            }),
+            Self::Tree(tree) => tree.items().try_fold(Vec::new(), |mut shapes, value| {
+                shapes.extend(value.to_shapes()?);
+                Ok(shapes)
+            }),
+            _ => Err(self.mismatch("Shape")),
+        }
+    }
+
+    /// Collects all points of a single point, a list of points
+    /// or a tree of points, flattening nested lists
+    pub fn to_points(&self) -> Result<Vec<piet::kurbo::Point>, EvalError> {
+        match self {
+            Self::List(values) => values.iter().try_fold(Vec::new(), |mut points, value| {
+                points.extend(value.to_points()?);
+                Ok(points)
+            }),
+            Self::Tree(tree) => tree.items().try_fold(Vec::new(), |mut points, value| {
+                points.extend(value.to_points()?);
+                Ok(points)
+            }),
+            _ => Ok(vec![self.as_point()?]),
+        }
+    }
+
+    /// Collects all numbers of a single number, a list of numbers
+    /// or a tree of numbers, flattening nested lists
+    pub fn to_numbers(&self) -> Result<Vec<f64>, EvalError> {
+        match self {
+            Self::List(values) => values.iter().try_fold(Vec::new(), |mut numbers, value| {
+                numbers.extend(value.to_numbers()?);
+                Ok(numbers)
+            }),
+            Self::Tree(tree) => tree.items().try_fold(Vec::new(), |mut numbers, value| {
+                numbers.extend(value.to_numbers()?);
+                Ok(numbers)
+            }),
+            _ => Ok(vec![self.as_number()?]),
+        }
+    }
+
+    /// Collects all transforms of a single transform, a list of
+    /// transforms or a tree of transforms, flattening nested lists
+    pub fn to_transforms(&self) -> Result<Vec<piet::kurbo::Affine>, EvalError> {
+        match self {
+            Self::List(values) => values.iter().try_fold(Vec::new(), |mut transforms, value| {
+                transforms.extend(value.to_transforms()?);
+                Ok(transforms)
+            }),
+            Self::Tree(tree) => tree.items().try_fold(Vec::new(), |mut transforms, value| {
+                transforms.extend(value.to_transforms()?);
+                Ok(transforms)
+            }),
+            _ => Ok(vec![self.as_transform()?]),
+        }
+    }
+
+    /// Collects all colors of a single color, a list of colors
+    /// or a tree of colors, flattening nested lists
+    pub fn to_colors(&self) -> Result<Vec<Color>, EvalError> {
+        match self {
+            Self::List(values) => values.iter().try_fold(Vec::new(), |mut colors, value| {
+                colors.extend(value.to_colors()?);
+                Ok(colors)
+            }),
+            Self::Tree(tree) => tree.items().try_fold(Vec::new(), |mut colors, value| {
+                colors.extend(value.to_colors()?);
+                Ok(colors)
+            }),
+            _ => Ok(vec![self.as_color()?]),
+        }
+    }
+}
+
+/// The type of the values a pin produces or expects,
+/// used to color pins and to check wires before connecting them
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    /// Works with values of every type
+    Any,
+    Number,
+    Bool,
+    Text,
+    Point,
+    Color,
+    Shape,
+    Curve,
+    Transform,
+    Drawing,
+}
+
+impl ValueType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Any => "Any",
+            Self::Number => "Number",
+            Self::Bool => "Bool",
+            Self::Text => "Text",
+            Self::Point => "Point",
+            Self::Color => "Color",
+            Self::Shape => "Shape",
+            Self::Curve => "Curve",
+            Self::Transform => "Transform",
+            Self::Drawing => "Drawing",
+        }
+    }
+
+    /// A neutral value of this type, `None` for types without one
+    pub fn default_value(&self) -> Option<Value> {
+        match self {
+            Self::Number => Some(Value::Number(0.0)),
+            Self::Bool => Some(Value::Bool(false)),
+            Self::Text => Some(Value::Text(String::new())),
+            Self::Point => Some(Value::Point(piet::kurbo::Point::ZERO)),
+            Self::Color => Some(Value::Color(Color::default())),
+            Self::Transform => Some(Value::Transform(piet::kurbo::Affine::IDENTITY)),
+            Self::Any | Self::Shape | Self::Curve | Self::Drawing => None,
+        }
+    }
+
+    pub fn color(&self) -> egui::Color32 {
+        match self {
+            Self::Number | Self::Bool => crate::NUMBER_COLOR,
+            Self::Point => crate::POINT_COLOR,
+            Self::Shape | Self::Curve | Self::Drawing => crate::SHAPE_COLOR,
+            Self::Color => crate::COLOR_COLOR,
+            Self::Transform => crate::TRANSFORM_COLOR,
+            Self::Any | Self::Text => crate::DATA_COLOR,
+        }
+    }
+
+    /// Whether values of this type can flow into an input of the target
+    /// type, either directly or by one of the conversions of [`Value`]
+    pub fn converts_to(self, target: ValueType) -> bool {
+        self == target
+            || matches!(
+                (self, target),
+                (Self::Any, _)
+                    | (_, Self::Any)
+                    | (_, Self::Text)
+                    | (Self::Bool | Self::Text, Self::Number)
+                    | (Self::Text, Self::Color)
+                    | (Self::Number, Self::Bool | Self::Point | Self::Color)
+                    | (Self::Shape, Self::Point)
+                    | (Self::Curve, Self::Shape)
+            )
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(value) => write!(f, "{}", format_float(*value)),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Text(value) => write!(f, "{value}"),
+            Self::Point(value) => write!(f, "{}", format_point(*value)),
+            Self::Color(value) => write!(f, "{}", value.to_hex()),
+            Self::Shape(shape) => write!(f, "{shape}"),
+            Self::Curve(curve) => write!(f, "{curve}"),
+            Self::Transform(affine) => {
+                let [a, b, c, d, e, f_] = affine.as_coeffs().map(format_float);
+                write!(f, "Transform [{a} {b} {c} {d} {e} {f_}]")
+            }
+            Self::Drawing(drawing) => write!(
+                f,
+                "Drawing {}x{}",
+                format_float(drawing.size.width),
+                format_float(drawing.size.height)
+            ),
+            Self::List(values) => write!(f, "List of {}", values.len()),
+            Self::Tree(tree) => write!(f, "Tree of {} branches", tree.branches().len()),
+        }
+    }
+}
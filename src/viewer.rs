@@ -1,15 +1,244 @@
+use std::collections::HashSet;
+
 use egui::{Color32, Style};
 use egui_snarl::{ui::SnarlViewer, Snarl};
 
-use crate::nodes::{self, InputNode, OutputNode};
+use crate::{
+    color::{Color, Interpolation},
+    nodes::{self, InputNode, OutputNode},
+    render::{self, ExportOptions, Format, Plot, PlotOptions, RasterOptions},
+    solver::{ListMatching, Results},
+};
+
+const ERROR_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 60, 60);
+
+/// Viewer state which has to outlive a single frame
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+pub(super) struct ViewerState {
+    /// Put a conversion node into wires between types which don't
+    /// convert implicitly, as far as [`nodes::conversion`] knows one
+    pub auto_convert: bool,
+    /// Why the last wire could not be connected
+    #[serde(skip)]
+    pub rejected: Option<String>,
+    /// Which part of the value the conversion node
+    /// of the last wire passes on
+    #[serde(skip)]
+    pub converted: Option<String>,
+    /// Settings of the last canvas export, reused for the next one
+    #[serde(default)]
+    pub export: RasterOptions,
+    #[serde(default)]
+    pub plot: PlotOptions,
+    /// Why the last canvas export failed
+    #[serde(skip)]
+    pub export_error: Option<String>,
+    /// Distances of the last export for a pen plotter
+    #[serde(skip)]
+    pub plot_report: Option<String>,
+    /// The node the pointer was last pressed on, which
+    /// receives the keys typed and the drags made since
+    #[serde(skip)]
+    pub active: Option<egui_snarl::NodeId>,
+    /// Nodes whose state the user may have changed this frame
+    #[serde(skip)]
+    pub edited: HashSet<egui_snarl::NodeId>,
+    /// Wires were connected or disconnected this frame
+    #[serde(skip)]
+    pub rewired: bool,
+}
 
-pub(super) struct NodeGraphViewer;
+/// If the user might be changing something this frame, by
+/// pressing, dragging or releasing the pointer or typing
+pub(super) fn editing(input: &egui::InputState) -> bool {
+    input.pointer.any_down()
+        || input.pointer.any_released()
+        || input.events.iter().any(|event| {
+            matches!(
+                event,
+                egui::Event::Key { .. } | egui::Event::Text(_) | egui::Event::Paste(_)
+            )
+        })
+}
 
-impl SnarlViewer<nodes::Nodes> for NodeGraphViewer {
+pub(super) struct NodeGraphViewer<'a> {
+    results: &'a Results,
+    state: &'a mut ViewerState,
+}
+
+impl<'a> NodeGraphViewer<'a> {
+    pub fn new(results: &'a Results, state: &'a mut ViewerState) -> Self {
+        Self { results, state }
+    }
+
+    /// Notes if the ui of a node was just pressed, and if
+    /// the node may have been edited through it
+    fn track(&mut self, node: egui_snarl::NodeId, ui: &egui::Ui) {
+        let (pressed, editing) = ui.input(|input| {
+            let pressed = input
+                .pointer
+                .any_pressed()
+                .then(|| input.pointer.press_origin())
+                .flatten();
+            (pressed, editing(input))
+        });
+        if pressed.is_some_and(|origin| ui.min_rect().contains(origin)) {
+            self.state.active = Some(node);
+        }
+        if editing && self.state.active == Some(node) {
+            self.state.edited.insert(node);
+        }
+    }
+
+    /// Exports the drawings of a canvas to files picked by the user
+    fn show_export_menu(&mut self, node: egui_snarl::NodeId, ui: &mut egui::Ui) {
+        let drawings = self
+            .results
+            .get(egui_snarl::OutPinId { node, output: 0 })
+            .map(render::drawings)
+            .unwrap_or_default();
+        ui.add_enabled_ui(!drawings.is_empty(), |ui| {
+            ui.menu_button("Export", |ui| {
+                let export = &mut self.state.export;
+                ui.horizontal(|ui| {
+                    ui.label("Scale");
+                    ui.add(
+                        egui::DragValue::new(&mut export.scale)
+                            .clamp_range(0.1..=16.0)
+                            .speed(0.1)
+                            .suffix("x"),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    let mut filled = export.background.is_some();
+                    if ui.checkbox(&mut filled, "Background").changed() {
+                        export.background = filled.then_some(Color::WHITE);
+                    }
+                    if let Some(color) = &mut export.background {
+                        let mut rgba = [color.r, color.g, color.b, color.a].map(|c| c as f32);
+                        if ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed() {
+                            let [r, g, b, a] = rgba.map(f64::from);
+                            *color = Color::rgba(r, g, b, a);
+                        }
+                    }
+                });
+                ui.separator();
+                for format in [Format::Svg, Format::Png, Format::Pdf] {
+                    self.show_export_button(node, &drawings, format, ui);
+                }
+
+                ui.separator();
+                ui.label("Pen plotter");
+                let plot = &mut self.state.plot;
+                egui::Grid::new("plot_options").show(ui, |ui| {
+                    ui.label("Feed rate");
+                    ui.add(egui::DragValue::new(&mut plot.feed_rate).suffix(" mm/min"));
+                    ui.end_row();
+                    ui.label("Travel rate");
+                    ui.add(egui::DragValue::new(&mut plot.travel_rate).suffix(" mm/min"));
+                    ui.end_row();
+                    ui.label("Pen up");
+                    ui.text_edit_singleline(&mut plot.pen_up);
+                    ui.end_row();
+                    ui.label("Pen down");
+                    ui.text_edit_singleline(&mut plot.pen_down);
+                    ui.end_row();
+                });
+                for format in [Format::Gcode, Format::Hpgl] {
+                    self.show_export_button(node, &drawings, format, ui);
+                }
+                if let Some(report) = &self.state.plot_report {
+                    ui.label(report);
+                }
+            });
+        });
+    }
+
+    fn show_export_button(
+        &mut self,
+        node: egui_snarl::NodeId,
+        drawings: &[&render::Drawing],
+        format: Format,
+        ui: &mut egui::Ui,
+    ) {
+        let label = format!("{}…", format.extension().to_uppercase());
+        if !ui.button(label).clicked() {
+            return;
+        }
+        let options = ExportOptions {
+            raster: self.state.export,
+            plot: self.state.plot.clone(),
+        };
+        match export_drawings(node, drawings, format, &options) {
+            Ok(exported) => {
+                self.state.export_error = None;
+                if exported && format.is_plot() {
+                    let reports = drawings
+                        .iter()
+                        .map(|drawing| Plot::new(drawing, &options.plot).report())
+                        .collect::<Vec<_>>();
+                    self.state.plot_report = Some(reports.join("\n"));
+                }
+            }
+            Err(error) => {
+                self.state.export_error = Some(format!("Could not export the canvas: {error:#}"));
+            }
+        }
+    }
+}
+
+/// Asks where to write the drawings, appending the index of the drawing
+/// to the file name if there is more than one. Pdf files get a page
+/// for every drawing instead. Returns if anything was written
+fn export_drawings(
+    node: egui_snarl::NodeId,
+    drawings: &[&render::Drawing],
+    format: Format,
+    options: &ExportOptions,
+) -> anyhow::Result<bool> {
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter(format.extension(), &[format.extension()])
+        .set_file_name(format!("canvas{}.{}", node.0, format.extension()))
+        .save_file()
+    else {
+        return Ok(false);
+    };
+    let path = path.with_extension(format.extension());
+    if format == Format::Pdf {
+        std::fs::write(&path, render::pdf::to_pdf(drawings))?;
+        return Ok(true);
+    }
+    for (index, drawing) in drawings.iter().enumerate() {
+        let path = match drawings.len() {
+            1 => path.clone(),
+            _ => render::suffixed(&path, &index.to_string()),
+        };
+        std::fs::write(&path, drawing.export(format, options)?)?;
+    }
+    Ok(true)
+}
+
+impl SnarlViewer<nodes::Nodes> for NodeGraphViewer<'_> {
     fn title(&mut self, node: &nodes::Nodes) -> String {
         node.title()
     }
 
+    fn show_header(
+        &mut self,
+        node: egui_snarl::NodeId,
+        _inputs: &[egui_snarl::InPin],
+        _outputs: &[egui_snarl::OutPin],
+        ui: &mut egui::Ui,
+        _scale: f32,
+        snarl: &mut Snarl<nodes::Nodes>,
+    ) {
+        ui.label(self.title(&snarl[node]));
+        if let Some(error) = self.results.error(node) {
+            ui.label(egui::RichText::new("⚠").color(ERROR_COLOR).strong())
+                .on_hover_text(error.to_string());
+        }
+    }
+
     fn outputs(&mut self, node: &nodes::Nodes) -> usize {
         node.outputs()
     }
@@ -25,17 +254,151 @@ impl SnarlViewer<nodes::Nodes> for NodeGraphViewer {
         scale: f32,
         snarl: &mut egui_snarl::Snarl<nodes::Nodes>,
     ) -> egui_snarl::ui::PinInfo {
-        match &mut snarl[pin.id.node] {
+        let info = match &mut snarl[pin.id.node] {
             nodes::Nodes::ConstantValueNode(_) => unreachable!(),
-            nodes::Nodes::Sink(_) => nodes::sink::show_input(pin, ui, scale, &snarl),
-            nodes::Nodes::Range(_) => nodes::range::RangeNode::show_input(pin, ui, scale, snarl),
-            nodes::Nodes::Point(_) => nodes::point::PointNode::show_input(pin, ui, scale, snarl),
-            nodes::Nodes::Circle(_) => nodes::circle::CircleNode::show_input(pin, ui, scale, snarl),
-            nodes::Nodes::Canvas(_) => nodes::canvas::CanvasNode::show_input(pin, ui, scale, snarl),
+            nodes::Nodes::Math(_) => {
+                nodes::math::MathNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Function(_) => {
+                nodes::math::FunctionNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Clamp(_) => {
+                nodes::math::ClampNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Lerp(_) => {
+                nodes::math::LerpNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Remap(_) => {
+                nodes::math::RemapNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Sink(_) => nodes::sink::show_input(pin, ui, scale, self.results),
+            nodes::Nodes::Range(_) => {
+                nodes::range::RangeNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Point(_) => {
+                nodes::point::PointNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::DeconstructPoint(_) => {
+                nodes::point::DeconstructPointNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Circle(_) => {
+                nodes::circle::CircleNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Rectangle(_) => {
+                nodes::primitive::RectangleNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::RoundedRectangle(_) => {
+                nodes::primitive::RoundedRectangleNode::show_input(
+                    pin,
+                    ui,
+                    scale,
+                    snarl,
+                    self.results,
+                )
+            }
+            nodes::Nodes::Ellipse(_) => {
+                nodes::primitive::EllipseNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Line(_) => {
+                nodes::primitive::LineNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Arc(_) => {
+                nodes::primitive::ArcNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Polyline(_) => {
+                nodes::primitive::PolylineNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Polygon(_) => {
+                nodes::primitive::PolygonNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Bezier(_) => {
+                nodes::curve::BezierNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::CatmullRom(_) => {
+                nodes::curve::CatmullRomNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Spline(_) => {
+                nodes::curve::SplineNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Nurbs(_) => {
+                nodes::curve::NurbsNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Translate(_) => {
+                nodes::transform::TranslateNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Rotate(_) => {
+                nodes::transform::RotateNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Scale(_) => {
+                nodes::transform::ScaleNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Mirror(_) => {
+                nodes::transform::MirrorNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Compose(_) => {
+                nodes::transform::ComposeNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Canvas(_) => {
+                nodes::canvas::CanvasNode::show_input(pin, ui, scale, snarl, self.results)
+            }
             nodes::Nodes::RepeatShape(_) => {
-                nodes::repeat::RepeatShapeNode::show_input(pin, ui, scale, snarl)
+                nodes::repeat::RepeatShapeNode::show_input(pin, ui, scale, snarl, self.results)
             }
-        }
+            nodes::Nodes::PolarArray(_) => {
+                nodes::repeat::PolarArrayNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::LinearArray(_) => {
+                nodes::repeat::LinearArrayNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::GridArray(_) => {
+                nodes::repeat::GridArrayNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Graft(_) => {
+                nodes::tree::GraftNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Flatten(_) => {
+                nodes::tree::FlattenNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Simplify(_) => {
+                nodes::tree::SimplifyNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::PathMapper(_) => {
+                nodes::tree::PathMapperNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::BranchItem(_) => {
+                nodes::tree::BranchItemNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::RgbColor(_) => {
+                nodes::color::RgbColorNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::HsvColor(_) => {
+                nodes::color::HsvColorNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::OklchColor(_) => {
+                nodes::color::OklchColorNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::HexColor(_) => {
+                nodes::color::HexColorNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Gradient(_) => {
+                nodes::color::GradientNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Harmony(_) => {
+                nodes::color::HarmonyNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Palette(_) => unreachable!(),
+            nodes::Nodes::Style(_) => {
+                nodes::style::StyleNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Parameter(_) => {
+                nodes::parameter::ParameterNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Output(_) => {
+                nodes::parameter::GraphOutputNode::show_input(pin, ui, scale, snarl, self.results)
+            }
+        };
+        self.track(pin.id.node, ui);
+        info
     }
 
     fn show_output(
@@ -45,39 +408,308 @@ impl SnarlViewer<nodes::Nodes> for NodeGraphViewer {
         scale: f32,
         snarl: &mut egui_snarl::Snarl<nodes::Nodes>,
     ) -> egui_snarl::ui::PinInfo {
-        match &mut snarl[pin.id.node] {
+        let info = match &mut snarl[pin.id.node] {
             nodes::Nodes::ConstantValueNode(ref mut node) => node.show_output(ui),
+            nodes::Nodes::Math(_) => {
+                nodes::math::MathNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Function(_) => {
+                nodes::math::FunctionNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Clamp(_) => {
+                nodes::math::ClampNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Lerp(_) => {
+                nodes::math::LerpNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Remap(_) => {
+                nodes::math::RemapNode::show_output(pin, ui, scale, snarl, self.results)
+            }
             nodes::Nodes::Sink(_) => unreachable!(),
-            nodes::Nodes::Range(_) => nodes::range::RangeNode::show_output(pin, ui, scale, snarl),
-            nodes::Nodes::Point(_) => nodes::point::PointNode::show_output(pin, ui, scale, snarl),
+            nodes::Nodes::Range(_) => {
+                nodes::range::RangeNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Point(_) => {
+                nodes::point::PointNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::DeconstructPoint(_) => {
+                nodes::point::DeconstructPointNode::show_output(pin, ui, scale, snarl, self.results)
+            }
             nodes::Nodes::Circle(_) => {
-                nodes::circle::CircleNode::show_output(pin, ui, scale, snarl)
+                nodes::circle::CircleNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Rectangle(_) => {
+                nodes::primitive::RectangleNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::RoundedRectangle(_) => {
+                nodes::primitive::RoundedRectangleNode::show_output(
+                    pin,
+                    ui,
+                    scale,
+                    snarl,
+                    self.results,
+                )
+            }
+            nodes::Nodes::Ellipse(_) => {
+                nodes::primitive::EllipseNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Line(_) => {
+                nodes::primitive::LineNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Arc(_) => {
+                nodes::primitive::ArcNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Polyline(_) => {
+                nodes::primitive::PolylineNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Polygon(_) => {
+                nodes::primitive::PolygonNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Bezier(_) => {
+                nodes::curve::BezierNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::CatmullRom(_) => {
+                nodes::curve::CatmullRomNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Spline(_) => {
+                nodes::curve::SplineNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Nurbs(_) => {
+                nodes::curve::NurbsNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Translate(_) => {
+                nodes::transform::TranslateNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Rotate(_) => {
+                nodes::transform::RotateNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Scale(_) => {
+                nodes::transform::ScaleNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Mirror(_) => {
+                nodes::transform::MirrorNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Compose(_) => {
+                nodes::transform::ComposeNode::show_output(pin, ui, scale, snarl, self.results)
             }
             nodes::Nodes::Canvas(_) => {
-                nodes::canvas::CanvasNode::show_output(pin, ui, scale, snarl)
+                nodes::canvas::CanvasNode::show_output(pin, ui, scale, snarl, self.results)
             }
             nodes::Nodes::RepeatShape(_) => {
-                nodes::repeat::RepeatShapeNode::show_output(pin, ui, scale, snarl)
+                nodes::repeat::RepeatShapeNode::show_output(pin, ui, scale, snarl, self.results)
             }
-        }
+            nodes::Nodes::PolarArray(_) => {
+                nodes::repeat::PolarArrayNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::LinearArray(_) => {
+                nodes::repeat::LinearArrayNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::GridArray(_) => {
+                nodes::repeat::GridArrayNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Graft(_) => {
+                nodes::tree::GraftNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Flatten(_) => {
+                nodes::tree::FlattenNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Simplify(_) => {
+                nodes::tree::SimplifyNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::PathMapper(_) => {
+                nodes::tree::PathMapperNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::BranchItem(_) => {
+                nodes::tree::BranchItemNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::RgbColor(_) => {
+                nodes::color::RgbColorNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::HsvColor(_) => {
+                nodes::color::HsvColorNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::OklchColor(_) => {
+                nodes::color::OklchColorNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::HexColor(_) => {
+                nodes::color::HexColorNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Gradient(_) => {
+                nodes::color::GradientNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Palette(_) => {
+                nodes::color::PaletteNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Harmony(_) => {
+                nodes::color::HarmonyNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Style(_) => {
+                nodes::style::StyleNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Parameter(_) => {
+                nodes::parameter::ParameterNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+            nodes::Nodes::Output(_) => {
+                nodes::parameter::GraphOutputNode::show_output(pin, ui, scale, snarl, self.results)
+            }
+        };
+        self.track(pin.id.node, ui);
+        info
     }
 
     fn input_color(
         &mut self,
         pin: &egui_snarl::InPin,
-        style: &Style,
+        _style: &Style,
         snarl: &mut egui_snarl::Snarl<nodes::Nodes>,
     ) -> Color32 {
-        crate::NUMBER_COLOR
+        snarl[pin.id.node].input_type(pin.id.input).color()
     }
 
     fn output_color(
         &mut self,
         pin: &egui_snarl::OutPin,
-        style: &Style,
+        _style: &Style,
         snarl: &mut egui_snarl::Snarl<nodes::Nodes>,
     ) -> Color32 {
-        crate::NUMBER_COLOR
+        snarl[pin.id.node].output_type(pin.id.output).color()
+    }
+
+    /// Only connects pins whose types convert into each other, or puts
+    /// a conversion node in between if that is enabled and one exists
+    fn connect(
+        &mut self,
+        from: &egui_snarl::OutPin,
+        to: &egui_snarl::InPin,
+        snarl: &mut Snarl<nodes::Nodes>,
+    ) {
+        let from_type = snarl[from.id.node].output_type(from.id.output);
+        let to_type = snarl[to.id.node].input_type(to.id.input);
+        if from_type.converts_to(to_type) {
+            snarl.connect(from.id, to.id);
+            self.state.rewired = true;
+            self.state.rejected = None;
+            self.state.converted = None;
+            return;
+        }
+
+        if self.state.auto_convert {
+            if let Some((node, output, part)) = nodes::conversion(from_type, to_type) {
+                let positions = snarl
+                    .nodes_pos_ids()
+                    .filter(|(id, _, _)| *id == from.id.node || *id == to.id.node)
+                    .map(|(_, pos, _)| pos.to_vec2())
+                    .collect::<Vec<_>>();
+                let pos = positions
+                    .iter()
+                    .fold(egui::Vec2::ZERO, |sum, pos| sum + *pos)
+                    / positions.len() as f32;
+                let converted = format!(
+                    "Put a {} node between, which only passes on the {part} of the {}. Wire another of its outputs to pass on a different part",
+                    node.title(),
+                    from_type.name()
+                );
+                let id = snarl.insert_node(pos.to_pos2(), node);
+                snarl.connect(from.id, egui_snarl::InPinId { node: id, input: 0 });
+                snarl.connect(egui_snarl::OutPinId { node: id, output }, to.id);
+                self.state.rewired = true;
+                self.state.rejected = None;
+                self.state.converted = Some(converted);
+                return;
+            }
+        }
+
+        self.state.converted = None;
+        self.state.rejected = Some(format!(
+            "Can't connect a {} output to a {} input",
+            from_type.name(),
+            to_type.name()
+        ));
+    }
+
+    fn disconnect(
+        &mut self,
+        from: &egui_snarl::OutPin,
+        to: &egui_snarl::InPin,
+        snarl: &mut Snarl<nodes::Nodes>,
+    ) {
+        self.state.rewired |= snarl.disconnect(from.id, to.id);
+    }
+
+    fn drop_outputs(&mut self, pin: &egui_snarl::OutPin, snarl: &mut Snarl<nodes::Nodes>) {
+        self.state.rewired |= snarl.drop_outputs(pin.id) > 0;
+    }
+
+    fn drop_inputs(&mut self, pin: &egui_snarl::InPin, snarl: &mut Snarl<nodes::Nodes>) {
+        self.state.rewired |= snarl.drop_inputs(pin.id) > 0;
+    }
+
+    fn node_menu(
+        &mut self,
+        node: egui_snarl::NodeId,
+        _inputs: &[egui_snarl::InPin],
+        _outputs: &[egui_snarl::OutPin],
+        ui: &mut egui::Ui,
+        _scale: f32,
+        snarl: &mut Snarl<nodes::Nodes>,
+    ) {
+        match &mut snarl[node] {
+            nodes::Nodes::Parameter(parameter) => parameter.show_settings(ui),
+            nodes::Nodes::Output(output) => {
+                ui.label("Description");
+                ui.text_edit_multiline(&mut output.description);
+            }
+            nodes::Nodes::Style(style) => style.show_settings(ui),
+            nodes::Nodes::Math(math) => {
+                for operation in nodes::math::Operation::ALL {
+                    ui.radio_value(&mut math.operation, operation, operation.name());
+                }
+            }
+            nodes::Nodes::Function(function) => {
+                for option in nodes::math::Function::ALL {
+                    ui.radio_value(&mut function.function, option, option.name());
+                }
+            }
+            nodes::Nodes::Gradient(gradient) => {
+                ui.horizontal(|ui| {
+                    ui.label("Blend in");
+                    for space in Interpolation::ALL {
+                        ui.radio_value(&mut gradient.space, space, space.name());
+                    }
+                });
+            }
+            nodes::Nodes::Palette(palette) => {
+                for option in nodes::color::Palette::ALL {
+                    ui.radio_value(&mut palette.palette, option, option.name());
+                }
+            }
+            nodes::Nodes::Harmony(harmony) => {
+                for option in nodes::color::Harmony::ALL {
+                    ui.radio_value(&mut harmony.harmony, option, option.name());
+                }
+            }
+            nodes::Nodes::Canvas(canvas) => {
+                ui.horizontal(|ui| {
+                    ui.label("Unit");
+                    for unit in render::Unit::ALL {
+                        ui.radio_value(&mut canvas.unit, unit, unit.name());
+                    }
+                });
+                self.show_export_menu(node, ui);
+            }
+            _ => {}
+        }
+        if let Some(matching) = snarl[node].matching_mut() {
+            ui.label("List matching");
+            for option in ListMatching::ALL {
+                if ui.radio_value(matching, option, option.name()).clicked() {
+                    ui.close_menu();
+                }
+            }
+        }
+        if ui.input(editing) {
+            self.state.edited.insert(node);
+        }
     }
 
     fn graph_menu(
@@ -87,11 +719,50 @@ impl SnarlViewer<nodes::Nodes> for NodeGraphViewer {
         _scale: f32,
         snarl: &mut Snarl<nodes::Nodes>,
     ) {
+        ui.checkbox(&mut self.state.auto_convert, "Insert conversion nodes")
+            .on_hover_text(
+                "Wiring a point to a number input puts a Deconstruct Point node between",
+            );
+        ui.separator();
         ui.label("Add node");
         if ui.button("Constant").clicked() {
             snarl.insert_node(pos, nodes::Nodes::ConstantValueNode(nodes::constant_value::ConstantValueNode::default()));
             ui.close_menu();
         }
+        ui.menu_button("Math", |ui| {
+            for operation in nodes::math::Operation::ALL {
+                if ui.button(operation.name()).clicked() {
+                    snarl.insert_node(
+                        pos,
+                        nodes::Nodes::Math(nodes::math::MathNode::new(operation)),
+                    );
+                    ui.close_menu();
+                }
+            }
+            ui.separator();
+            for function in nodes::math::Function::ALL {
+                if ui.button(function.name()).clicked() {
+                    snarl.insert_node(
+                        pos,
+                        nodes::Nodes::Function(nodes::math::FunctionNode::new(function)),
+                    );
+                    ui.close_menu();
+                }
+            }
+            ui.separator();
+            if ui.button("Clamp").clicked() {
+                snarl.insert_node(pos, nodes::Nodes::Clamp(nodes::math::ClampNode::default()));
+                ui.close_menu();
+            }
+            if ui.button("Lerp").clicked() {
+                snarl.insert_node(pos, nodes::Nodes::Lerp(nodes::math::LerpNode::default()));
+                ui.close_menu();
+            }
+            if ui.button("Remap").clicked() {
+                snarl.insert_node(pos, nodes::Nodes::Remap(nodes::math::RemapNode::default()));
+                ui.close_menu();
+            }
+        });
         if ui.button("Sink").clicked() {
             snarl.insert_node(pos, nodes::Nodes::Sink(nodes::sink::SinkNode));
             ui.close_menu();
@@ -104,6 +775,13 @@ impl SnarlViewer<nodes::Nodes> for NodeGraphViewer {
             snarl.insert_node(pos, nodes::Nodes::Point(nodes::point::PointNode::default()));
             ui.close_menu();
         }
+        if ui.button("Deconstruct Point").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::DeconstructPoint(nodes::point::DeconstructPointNode::default()),
+            );
+            ui.close_menu();
+        }
         if ui.button("Circle").clicked() {
             snarl.insert_node(
                 pos,
@@ -111,6 +789,112 @@ impl SnarlViewer<nodes::Nodes> for NodeGraphViewer {
             );
             ui.close_menu();
         }
+        if ui.button("Rectangle").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Rectangle(nodes::primitive::RectangleNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Rounded Rectangle").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::RoundedRectangle(nodes::primitive::RoundedRectangleNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Ellipse").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Ellipse(nodes::primitive::EllipseNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Line").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Line(nodes::primitive::LineNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Arc").clicked() {
+            snarl.insert_node(pos, nodes::Nodes::Arc(nodes::primitive::ArcNode::default()));
+            ui.close_menu();
+        }
+        if ui.button("Polyline").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Polyline(nodes::primitive::PolylineNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Polygon").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Polygon(nodes::primitive::PolygonNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Bezier").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Bezier(nodes::curve::BezierNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Catmull-Rom").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::CatmullRom(nodes::curve::CatmullRomNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Spline").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Spline(nodes::curve::SplineNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("NURBS").clicked() {
+            snarl.insert_node(pos, nodes::Nodes::Nurbs(nodes::curve::NurbsNode::default()));
+            ui.close_menu();
+        }
+        if ui.button("Translate").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Translate(nodes::transform::TranslateNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Rotate").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Rotate(nodes::transform::RotateNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Scale").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Scale(nodes::transform::ScaleNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Mirror").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Mirror(nodes::transform::MirrorNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Compose").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Compose(nodes::transform::ComposeNode::default()),
+            );
+            ui.close_menu();
+        }
         if ui.button("Canvas").clicked() {
             snarl.insert_node(
                 pos,
@@ -125,5 +909,119 @@ impl SnarlViewer<nodes::Nodes> for NodeGraphViewer {
             );
             ui.close_menu();
         }
+        if ui.button("Polar Array").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::PolarArray(nodes::repeat::PolarArrayNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Linear Array").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::LinearArray(nodes::repeat::LinearArrayNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Grid Array").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::GridArray(nodes::repeat::GridArrayNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("RGB").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::RgbColor(nodes::color::RgbColorNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("HSV").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::HsvColor(nodes::color::HsvColorNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("OKLCH").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::OklchColor(nodes::color::OklchColorNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Hex Color").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::HexColor(nodes::color::HexColorNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Gradient").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Gradient(nodes::color::GradientNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Palette").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Palette(nodes::color::PaletteNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Harmony").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Harmony(nodes::color::HarmonyNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Style").clicked() {
+            snarl.insert_node(pos, nodes::Nodes::Style(nodes::style::StyleNode::default()));
+            ui.close_menu();
+        }
+        if ui.button("Parameter").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Parameter(nodes::parameter::ParameterNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Output").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::Output(nodes::parameter::GraphOutputNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Graft").clicked() {
+            snarl.insert_node(pos, nodes::Nodes::Graft(nodes::tree::GraftNode));
+            ui.close_menu();
+        }
+        if ui.button("Flatten").clicked() {
+            snarl.insert_node(pos, nodes::Nodes::Flatten(nodes::tree::FlattenNode));
+            ui.close_menu();
+        }
+        if ui.button("Simplify").clicked() {
+            snarl.insert_node(pos, nodes::Nodes::Simplify(nodes::tree::SimplifyNode));
+            ui.close_menu();
+        }
+        if ui.button("Path Mapper").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::PathMapper(nodes::tree::PathMapperNode::default()),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Branch Item").clicked() {
+            snarl.insert_node(
+                pos,
+                nodes::Nodes::BranchItem(nodes::tree::BranchItemNode::default()),
+            );
+            ui.close_menu();
+        }
     }
 }
@@ -0,0 +1,102 @@
+use egui_snarl::{InPinId, NodeId, OutPinId};
+use recoded::{
+    document::{Document, VERSION},
+    solver,
+    value::Value,
+};
+
+/// A sketch saved by every past version of the file format, each of
+/// which has to load into the same graph after migrating it.
+/// Add the fixture of the previous version here when bumping [`VERSION`].
+const FIXTURES: [(u32, &str); 3] = [
+    (0, include_str!("fixtures/v0.recoded")),
+    (1, include_str!("fixtures/v1.recoded")),
+    (2, include_str!("fixtures/v2.recoded")),
+];
+
+fn titles(document: &Document) -> Vec<String> {
+    let mut nodes = document
+        .snarl
+        .node_ids()
+        .map(|(id, node)| (id, node.title()))
+        .collect::<Vec<_>>();
+    nodes.sort();
+    nodes.into_iter().map(|(_, title)| title).collect()
+}
+
+#[test]
+fn fixtures_cover_every_version() {
+    let versions = FIXTURES.map(|(version, _)| version);
+    assert_eq!(versions.to_vec(), (0..=VERSION).collect::<Vec<_>>());
+}
+
+#[test]
+fn fixtures_load_the_same_graph() {
+    for (version, json) in FIXTURES {
+        let document = Document::from_json(json)
+            .unwrap_or_else(|error| panic!("Fixture of version {version} failed: {error:#}"));
+        assert_eq!(
+            titles(&document),
+            [
+                "Constant",
+                "Point",
+                "Circle",
+                "RepeatShape",
+                "Canvas",
+                "Sink",
+                "Range"
+            ],
+            "Nodes of version {version}"
+        );
+
+        let circle = document.snarl.in_pin(InPinId {
+            node: NodeId(2),
+            input: 1,
+        });
+        assert_eq!(
+            circle.remotes,
+            [OutPinId {
+                node: NodeId(0),
+                output: 0
+            }],
+            "Wires of version {version}"
+        );
+
+        let results = solver::solve(&document.snarl);
+        assert_eq!(results.errors().count(), 0, "Errors of version {version}");
+        assert!(
+            matches!(
+                results.get(OutPinId {
+                    node: NodeId(4),
+                    output: 0
+                }),
+                Some(Value::Drawing(_))
+            ),
+            "Canvas of version {version}"
+        );
+    }
+}
+
+#[test]
+fn saved_documents_load_unchanged() {
+    let (_, json) = FIXTURES[0];
+    let document = Document::from_json(json).unwrap();
+    let reloaded = Document::from_json(&document.to_json().unwrap()).unwrap();
+    assert_eq!(document.fingerprint(), reloaded.fingerprint());
+}
+
+#[test]
+fn newer_versions_are_rejected() {
+    let json = format!(
+        r#"{{"format": "recoded", "version": {}, "document": {{}}}}"#,
+        VERSION + 1
+    );
+    let error = Document::from_json(&json).err().unwrap();
+    assert!(error.to_string().contains("newer version"), "{error}");
+}
+
+#[test]
+fn other_json_is_rejected() {
+    assert!(Document::from_json(r#"{"hello": "world"}"#).is_err());
+    assert!(Document::from_json("[]").is_err());
+}
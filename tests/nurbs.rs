@@ -0,0 +1,199 @@
+use piet::kurbo::{CubicBez, ParamCurve, ParamCurveDeriv, Point, Shape};
+use recoded::{nurbs::Nurbs, solver::EvalError};
+
+const EPSILON: f64 = 1e-9;
+
+const HALF_SQRT_2: f64 = std::f64::consts::FRAC_1_SQRT_2;
+
+/// The unit circle as 4 rational quadratic arcs, one per quadrant
+fn unit_circle() -> Nurbs {
+    let points = [
+        (1.0, 0.0),
+        (1.0, 1.0),
+        (0.0, 1.0),
+        (-1.0, 1.0),
+        (-1.0, 0.0),
+        (-1.0, -1.0),
+        (0.0, -1.0),
+        (1.0, -1.0),
+        (1.0, 0.0),
+    ]
+    .map(Point::from)
+    .to_vec();
+    let weights = (0..9)
+        .map(|index| if index % 2 == 0 { 1.0 } else { HALF_SQRT_2 })
+        .collect();
+    let knots = vec![
+        0.0, 0.0, 0.0, 0.25, 0.25, 0.5, 0.5, 0.75, 0.75, 1.0, 1.0, 1.0,
+    ];
+    Nurbs::new(points, Some(weights), 2, Some(knots)).unwrap()
+}
+
+fn samples(count: usize) -> impl Iterator<Item = f64> {
+    (0..=count).map(move |index| index as f64 / count as f64)
+}
+
+#[test]
+fn quarter_circle_is_exact() {
+    let points = vec![
+        Point::new(1.0, 0.0),
+        Point::new(1.0, 1.0),
+        Point::new(0.0, 1.0),
+    ];
+    let curve = Nurbs::new(points, Some(vec![1.0, HALF_SQRT_2, 1.0]), 2, None).unwrap();
+    assert_eq!(curve.domain(), (0.0, 1.0));
+    for t in samples(64) {
+        let point = curve.point_at(t);
+        assert!((point.to_vec2().hypot() - 1.0).abs() < EPSILON, "{point:?}");
+        // Tangents of a circle are perpendicular to the radius
+        let derivative = curve.derivative_at(t);
+        assert!(point.to_vec2().dot(derivative).abs() < EPSILON);
+        assert!(derivative.cross(point.to_vec2()) < 0.0);
+    }
+    // The middle of the arc lies at 45 degrees
+    let middle = curve.point_at(0.5);
+    assert!((middle - Point::new(HALF_SQRT_2, HALF_SQRT_2)).hypot() < EPSILON);
+}
+
+#[test]
+fn full_circle_is_exact() {
+    let curve = unit_circle();
+    for t in samples(256) {
+        let point = curve.point_at(t);
+        assert!((point.to_vec2().hypot() - 1.0).abs() < EPSILON, "{point:?}");
+        assert!(point.to_vec2().dot(curve.derivative_at(t)).abs() < EPSILON);
+    }
+    for (t, expected) in [
+        (0.0, (1.0, 0.0)),
+        (0.25, (0.0, 1.0)),
+        (0.5, (-1.0, 0.0)),
+        (0.75, (0.0, -1.0)),
+        (1.0, (1.0, 0.0)),
+    ] {
+        assert!((curve.point_at(t) - Point::from(expected)).hypot() < EPSILON);
+    }
+}
+
+#[test]
+fn derivative_matches_finite_differences() {
+    let curve = unit_circle();
+    let step = 1e-6;
+    for t in samples(50).map(|t| 0.01 + t * 0.98) {
+        let numeric = (curve.point_at(t + step) - curve.point_at(t - step)) / (2.0 * step);
+        assert!((numeric - curve.derivative_at(t)).hypot() < 1e-5, "at {t}");
+    }
+}
+
+#[test]
+fn cubic_b_spline_matches_bezier() {
+    let points = [(0.0, 0.0), (10.0, 30.0), (40.0, -20.0), (50.0, 10.0)].map(Point::from);
+    let bezier = CubicBez::new(points[0], points[1], points[2], points[3]);
+    let curve = Nurbs::new(points.to_vec(), None, 3, None).unwrap();
+    for t in samples(32) {
+        assert!((curve.point_at(t) - bezier.eval(t)).hypot() < EPSILON);
+        let derivative = bezier.deriv().eval(t).to_vec2();
+        assert!((curve.derivative_at(t) - derivative).hypot() < EPSILON);
+    }
+}
+
+#[test]
+fn equal_weights_cancel_out() {
+    let points = [
+        (0.0, 0.0),
+        (10.0, 30.0),
+        (20.0, -5.0),
+        (40.0, 0.0),
+        (50.0, 20.0),
+    ]
+    .map(Point::from)
+    .to_vec();
+    let plain = Nurbs::new(points.clone(), None, 3, None).unwrap();
+    let weighted = Nurbs::new(points, Some(vec![2.5; 5]), 3, None).unwrap();
+    for t in samples(32) {
+        assert!((plain.point_at(t) - weighted.point_at(t)).hypot() < EPSILON);
+        assert!((plain.derivative_at(t) - weighted.derivative_at(t)).hypot() < EPSILON);
+    }
+}
+
+#[test]
+fn linear_curve_is_the_polyline() {
+    let points = [(0.0, 0.0), (10.0, 0.0), (10.0, 20.0)]
+        .map(Point::from)
+        .to_vec();
+    let curve = Nurbs::new(points, None, 1, None).unwrap();
+    assert!((curve.point_at(0.25) - Point::new(5.0, 0.0)).hypot() < EPSILON);
+    assert!((curve.point_at(0.5) - Point::new(10.0, 0.0)).hypot() < EPSILON);
+    assert!((curve.point_at(0.75) - Point::new(10.0, 10.0)).hypot() < EPSILON);
+}
+
+#[test]
+fn parameters_outside_are_clamped() {
+    let curve = unit_circle();
+    assert_eq!(curve.point_at(-1.0), curve.point_at(0.0));
+    assert_eq!(curve.point_at(2.0), curve.point_at(1.0));
+}
+
+#[test]
+fn bezier_approximation_stays_within_tolerance() {
+    let curve = unit_circle();
+    let path = curve.to_bezier(Nurbs::TOLERANCE);
+    // The curve is split at the knots of every quadrant
+    assert!(path.segments().count() >= 4);
+    for segment in path.segments() {
+        for t in samples(16) {
+            let distance = segment.eval(t).to_vec2().hypot();
+            assert!((distance - 1.0).abs() <= Nurbs::TOLERANCE, "{distance}");
+        }
+    }
+    assert!((path.area() - std::f64::consts::PI).abs() < 1e-2);
+}
+
+#[test]
+fn polynomial_spans_take_one_segment_each() {
+    let points = [
+        (0.0, 0.0),
+        (10.0, 30.0),
+        (20.0, -5.0),
+        (40.0, 0.0),
+        (50.0, 20.0),
+    ]
+    .map(Point::from)
+    .to_vec();
+    let path = Nurbs::new(points, None, 3, None).unwrap().to_bezier(1e-9);
+    assert_eq!(path.segments().count(), 2);
+}
+
+#[test]
+fn invalid_curves_are_rejected() {
+    let points = [(0.0, 0.0), (10.0, 0.0), (10.0, 20.0)]
+        .map(Point::from)
+        .to_vec();
+    let invalid = [
+        Nurbs::new(points.clone(), None, 0, None),
+        Nurbs::new(points.clone(), None, 3, None),
+        Nurbs::new(points.clone(), Some(vec![1.0, 1.0]), 2, None),
+        Nurbs::new(points.clone(), Some(vec![1.0, -1.0, 1.0]), 2, None),
+        Nurbs::new(points.clone(), None, 2, Some(vec![0.0, 0.0, 1.0, 1.0])),
+        Nurbs::new(
+            points.clone(),
+            None,
+            2,
+            Some(vec![0.0, 0.0, 0.0, 1.0, 0.5, 1.0]),
+        ),
+        Nurbs::new(points.clone(), None, 2, Some(vec![0.0; 6])),
+        Nurbs::new(points, None, usize::MAX, None),
+    ];
+    for curve in invalid {
+        assert!(matches!(curve, Err(EvalError::Domain(_))), "{curve:?}");
+    }
+}
+
+#[test]
+fn huge_degrees_are_rejected_when_loading() {
+    let json = format!(
+        r#"{{"control_points":[[0,0],[1,0],[1,1]],"weights":[1,1,1],"degree":{},"knots":[]}}"#,
+        usize::MAX
+    );
+    let error = serde_json::from_str::<Nurbs>(&json).unwrap_err();
+    assert!(error.to_string().contains("control points"), "{error}");
+}